@@ -0,0 +1,76 @@
+//! Times the broad phase on a crowd of a few thousand objects,
+//! run with `cargo bench --bench broadphase`
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use oganesson::{
+    possible_collisions, sweep_and_prune,
+    units::{m, s},
+    Float, Object, ObjectBuilder, STEP,
+};
+
+const OBJECTS: usize = 4000;
+const RUNS: u32 = 50;
+
+/// Objects of the default 1 m size scattered uniformly, `spacing` meters apart on average
+fn crowd(spacing: Float) -> Vec<Object<2>> {
+    let side = (OBJECTS as Float).sqrt() * spacing;
+    let mut rng = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+        (rng >> 40) as Float / (1u64 << 24) as Float * side
+    };
+    (0..OBJECTS)
+        .map(|_| ObjectBuilder::new_at([next(), next()] * m).build().unwrap())
+        .collect()
+}
+
+fn main() {
+    for spacing in [3.0, 8.0] {
+        let objects = crowd(spacing);
+        let pairs = possible_collisions(&objects, 32).len();
+        println!("{OBJECTS} objects {spacing} m apart, {pairs} overlapping pairs");
+        for leaf_size in [2, 4, 8, 16, 32, 64, 128] {
+            // The fastest run, the others only add the noise of the machine
+            let time = (0..RUNS)
+                .map(|_| {
+                    let start = Instant::now();
+                    black_box(possible_collisions(black_box(&objects), leaf_size));
+                    start.elapsed()
+                })
+                .min()
+                .unwrap();
+            println!("leaf size {leaf_size:>3}: {time:?}");
+        }
+    }
+
+    // A crowd drifting at 1 m/s barely reorders between substeps, which the sweep
+    // takes advantage of while the median splits start over every time
+    let mut objects = crowd(3.0);
+    let drift = |objects: &mut [Object<2>], substep: usize| {
+        for (i, object) in objects.iter_mut().enumerate() {
+            let angle = (i * 7 + substep) as Float * 0.01;
+            let velocity = [angle.cos(), angle.sin()] * m / s;
+            let position = object.position() + velocity * (STEP * s);
+            object.set_position(position).unwrap();
+        }
+    };
+    let mut order = Vec::new();
+    let (mut splits, mut sweep) = (Duration::ZERO, Duration::ZERO);
+    for substep in 0..RUNS as usize {
+        drift(&mut objects, substep);
+        let start = Instant::now();
+        black_box(possible_collisions(black_box(&objects), 32));
+        splits += start.elapsed();
+        let start = Instant::now();
+        black_box(sweep_and_prune(black_box(&objects), &mut order));
+        sweep += start.elapsed();
+    }
+    println!("slow crowd, median splits: {:?}", splits / RUNS);
+    println!("slow crowd, incremental sweep: {:?}", sweep / RUNS);
+}
@@ -0,0 +1,83 @@
+//! Times the gravity and Coulomb forces between every pair of a few thousand objects,
+//! run with `cargo bench --bench pair_forces`
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use oganesson::{
+    coulomb_force, gravitational_force,
+    units::{kg, m, C, N},
+    Float, Object, ObjectBuilder, Universe, Vector,
+};
+
+const OBJECTS: usize = 3000;
+const RUNS: u32 = 5;
+
+/// Charged objects too far apart to touch, so a step is mostly the pair forces
+fn crowd() -> Vec<Object<2>> {
+    let side = (OBJECTS as Float).sqrt() * 10.0;
+    let mut rng = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+        (rng >> 40) as Float / (1u64 << 24) as Float
+    };
+    (0..OBJECTS)
+        .map(|_| {
+            ObjectBuilder::new_at([next() * side, next() * side] * m)
+                .with_mass((1.0 + next()) * kg)
+                .with_charge((next() - 0.5) * 1e-6 * C)
+                .build()
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Every object summing the forces of all the others, each pair is evaluated twice
+fn naive(objects: &[Object<2>]) -> Vec<Vector<2>> {
+    objects
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            objects
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(Vector::zero() * N, |force, (_, b)| {
+                    force + gravitational_force(a, b) + coulomb_force(a, b)
+                })
+        })
+        .collect()
+}
+
+/// The fastest of `RUNS` runs of `f`, the others only add the noise of the machine
+fn time(mut f: impl FnMut()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let objects = crowd();
+    let naive = time(|| {
+        black_box(naive(black_box(&objects)));
+    });
+    println!("{OBJECTS} objects, naive double loop: {naive:?}");
+
+    // A step evaluates each pair once and applies the opposite force to the other object,
+    // on top of that it integrates and runs the broad phase
+    let mut universe = Universe::new();
+    for object in objects {
+        universe.add_object(object);
+    }
+    let step = time(|| universe.step_once());
+    println!("{OBJECTS} objects, whole substep: {step:?}");
+}
@@ -1,32 +1,401 @@
-use crate::{Object, Scalar, Vector};
+use crate::{
+    universe::{embed, flatten},
+    Float, Object, Quaternion, Scalar, Vector,
+};
 
-#[derive(Debug, Clone)]
-pub struct Collider<const N: usize> {
-    pub size: Scalar,
-    pub position: Vector<N>,
+#[derive(Debug, Clone, PartialEq)]
+pub enum Collider<const N: usize> {
+    Sphere {
+        size: Scalar,
+        position: Vector<N>,
+    },
+    /// Solid half-space below the surface `x·normal = offset`, `normal` is a dimensionless
+    /// unit vector pointing out of the solid
+    Plane {
+        normal: Vector<N>,
+        offset: Scalar,
+    },
+    /// Union of `parts` each placed by its transform relative to `position`,
+    /// for non-convex bodies. Contacts are reported for the deepest part
+    Compound {
+        position: Vector<N>,
+        parts: Vec<(Transform<N>, Collider<N>)>,
+    },
+}
+
+/// Placement of a part of a compound: turned by `orientation` about its own origin,
+/// then moved by `offset`. In 2D only rotations about z keep a part in the plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform<const N: usize> {
+    pub offset: Vector<N>,
+    pub orientation: Quaternion,
+}
+
+impl<const N: usize> Transform<N> {
+    /// Moves a part by `offset` without turning it
+    pub fn translation(offset: Vector<N>) -> Transform<N> {
+        Transform {
+            offset,
+            orientation: Quaternion::IDENTITY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColliderKind {
+    Sphere,
+    Plane,
+    Compound,
+}
+
+/// Axis aligned box spanning `min` to `max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox<const N: usize> {
+    pub min: Vector<N>,
+    pub max: Vector<N>,
+}
+
+impl<const N: usize> BoundingBox<N> {
+    #[track_caller]
+    pub fn contains(&self, point: Vector<N>) -> bool {
+        if point.dim() != self.min.dim() {
+            panic!(
+                "Cannot check a point of dimension {} against a box of dimension {}",
+                point.dim(),
+                self.min.dim()
+            );
+        }
+        (0..N).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Smallest box containing both boxes
+    #[track_caller]
+    pub fn union(self, other: BoundingBox<N>) -> BoundingBox<N> {
+        if self.min.dim() != other.min.dim() {
+            panic!(
+                "Cannot join boxes of dimensions {} and {}",
+                self.min.dim(),
+                other.min.dim()
+            );
+        }
+        let (mut min, mut max) = (self.min, self.max);
+        for i in 0..N {
+            min[i] = min[i].min(other.min[i]);
+            max[i] = max[i].max(other.max[i]);
+        }
+        BoundingBox { min, max }
+    }
 }
 
 impl<const N: usize> Collider<N> {
+    pub fn kind(&self) -> ColliderKind {
+        match self {
+            Collider::Sphere { .. } => ColliderKind::Sphere,
+            Collider::Plane { .. } => ColliderKind::Plane,
+            Collider::Compound { .. } => ColliderKind::Compound,
+        }
+    }
+
+    /// The same collider moved by `offset`
+    pub fn translated(&self, offset: Vector<N>) -> Collider<N> {
+        match *self {
+            Collider::Sphere { size, position } => Collider::Sphere {
+                size,
+                position: position + offset,
+            },
+            Collider::Plane { normal, offset: d } => Collider::Plane {
+                normal,
+                offset: d + offset.dot(normal),
+            },
+            Collider::Compound {
+                position,
+                ref parts,
+            } => Collider::Compound {
+                position: position + offset,
+                parts: parts.clone(),
+            },
+        }
+    }
+
+    /// The same collider turned by `rotation` about the origin
+    pub fn rotated(&self, rotation: Quaternion) -> Collider<N> {
+        let rotate = |v: Vector<N>| flatten(rotation.rotate(embed(v)));
+        match *self {
+            Collider::Sphere { size, position } => Collider::Sphere {
+                size,
+                position: rotate(position),
+            },
+            Collider::Plane { normal, offset } => Collider::Plane {
+                normal: rotate(normal),
+                offset,
+            },
+            Collider::Compound {
+                position,
+                ref parts,
+            } => Collider::Compound {
+                position: rotate(position),
+                parts: parts
+                    .iter()
+                    .map(|(transform, part)| {
+                        let transform = Transform {
+                            offset: rotate(transform.offset),
+                            orientation: rotation * transform.orientation,
+                        };
+                        (transform, part.clone())
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    /// Parts of a compound in world space, or the collider itself
+    fn parts(&self) -> Vec<Collider<N>> {
+        match self {
+            Collider::Compound { position, parts } => parts
+                .iter()
+                .map(|(transform, part)| {
+                    part.rotated(transform.orientation)
+                        .translated(transform.offset + *position)
+                })
+                .collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Planes are unbounded, their box spans all of space
+    pub fn bounding_box(&self) -> BoundingBox<N> {
+        match *self {
+            Collider::Sphere { size, position } => {
+                let extent = [size.value(); N] * size.dim();
+                BoundingBox {
+                    min: position - extent,
+                    max: position + extent,
+                }
+            }
+            Collider::Plane { offset, .. } => BoundingBox {
+                min: [Float::NEG_INFINITY; N] * offset.dim(),
+                max: [Float::INFINITY; N] * offset.dim(),
+            },
+            Collider::Compound { position, .. } => self
+                .parts()
+                .iter()
+                .map(Collider::bounding_box)
+                .reduce(BoundingBox::union)
+                .unwrap_or(BoundingBox {
+                    min: position,
+                    max: position,
+                }),
+        }
+    }
+
+    /// Penetration of `self` into `other`, pointing from `other` towards `self`
     pub fn collides(&self, other: &Collider<N>) -> Option<Vector<N>> {
-        let r1 = self.size;
-        let r2 = other.size;
-        let distance = self.position - other.position;
-        let direction = distance.normalized();
-        let distance = distance.magnitude().abs();
-        if distance >= r1 + r2 {
-            None
-        } else {
-            Some(direction * (r1 + r2 - distance))
+        match (self, other) {
+            (Collider::Compound { .. }, _) => self
+                .parts()
+                .iter()
+                .filter_map(|part| part.collides(other))
+                .max_by(|a, b| a.squared().value().total_cmp(&b.squared().value())),
+            (_, Collider::Compound { .. }) => other.collides(self).map(|v| -v),
+            (
+                &Collider::Sphere {
+                    size: r1,
+                    position: p1,
+                },
+                &Collider::Sphere {
+                    size: r2,
+                    position: p2,
+                },
+            ) => {
+                let distance = p1 - p2;
+                let direction = distance.normalized();
+                let distance = distance.magnitude().abs();
+                if distance >= r1 + r2 {
+                    None
+                } else {
+                    Some(direction * (r1 + r2 - distance))
+                }
+            }
+            (&Collider::Sphere { size, position }, &Collider::Plane { normal, offset }) => {
+                let height = position.dot(normal) - offset;
+                if height >= size {
+                    None
+                } else {
+                    Some(normal * (size - height))
+                }
+            }
+            (Collider::Plane { .. }, Collider::Sphere { .. }) => other.collides(self).map(|v| -v),
+            (Collider::Plane { .. }, Collider::Plane { .. }) => None,
+        }
+    }
+
+    /// Distance along the ray from `origin` in the unit `direction` to the collider's surface,
+    /// zero if `origin` is inside it
+    pub fn raycast(&self, origin: Vector<N>, direction: Vector<N>) -> Option<Scalar> {
+        match *self {
+            Collider::Sphere { size, position } => {
+                let to_center = position - origin;
+                let along = to_center.dot(direction);
+                let miss = to_center.squared() - along.squared();
+                if to_center.squared() <= size.squared() {
+                    return Some(along * 0.0);
+                }
+                if along < 0.0 || miss > size.squared() {
+                    return None;
+                }
+                Some(along - (size.squared() - miss).sqrt())
+            }
+            Collider::Plane { normal, offset } => {
+                let height = origin.dot(normal) - offset;
+                let approach = direction.dot(normal);
+                if height <= 0.0 {
+                    Some(height * 0.0)
+                } else if approach >= 0.0 {
+                    None
+                } else {
+                    Some(-height / approach)
+                }
+            }
+            Collider::Compound { .. } => self
+                .parts()
+                .iter()
+                .filter_map(|part| part.raycast(origin, direction))
+                .min_by(|a, b| a.value().total_cmp(&b.value())),
         }
     }
 
+    /// Earliest time within `[0, dt]` at which two spheres moving at constant velocities touch,
+    /// zero if they already overlap and `None` if they don't meet in time
+    pub fn sphere_sweep_toi(
+        center_a: Vector<N>,
+        radius_a: Scalar,
+        vel_a: Vector<N>,
+        center_b: Vector<N>,
+        radius_b: Scalar,
+        vel_b: Vector<N>,
+        dt: Scalar,
+    ) -> Option<Scalar> {
+        let (p, v, reach) = (center_b - center_a, vel_b - vel_a, radius_a + radius_b);
+        let c = p.squared() - reach.squared();
+        if c <= 0.0 {
+            return Some(dt * 0.0);
+        }
+        // |p + v t| = reach, approaching only if b < 0
+        let (a, b) = (v.squared(), 2.0 * p.dot(v));
+        let discriminant = b.squared() - 4.0 * a * c;
+        if b >= 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let toi = (-b - discriminant.sqrt()) / (2.0 * a);
+        (toi <= dt).then_some(toi)
+    }
+
     pub fn is_collision(&self, other: &Collider<N>) -> bool {
         self.collides(other).is_some()
     }
 }
 
-pub fn possible_collisions<const N: usize>(objects: &[Object<N>]) -> Vec<(usize, usize)> {
-    if objects.len() < 2 {
+/// A contact between the objects `a` and `b`, solved with sequential impulses.
+///
+/// Impulses are accumulated over the solver iterations and clamped so the total normal
+/// impulse never pulls the objects together and friction stays within `μ` times it.
+/// A single iteration is the closed form 1D solution along the normal, it conserves momentum
+/// and satisfies `(v_a' - v_b')·n = -e (u_a - u_b)·n`.
+/// The velocities passed to [`Contact::solve`] are those of the touching points.
+pub(crate) struct Contact<const N: usize> {
+    pub a: usize,
+    pub b: usize,
+    /// Unit normal pointing from `b` to `a`
+    pub normal: Vector<N>,
+    /// Separating normal velocity the solver aims for
+    target: Scalar,
+    friction: Float,
+    reduced_mass: Scalar,
+    /// Reduced mass of the touching points along the surface, lower than `reduced_mass`
+    /// when friction also spins the objects
+    tangent_mass: Scalar,
+    normal_impulse: Scalar,
+    tangent_impulse: Vector<N>,
+}
+
+impl<const N: usize> Contact<N> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        (a, u_a, m_a): (usize, Vector<N>, Scalar),
+        (b, u_b, m_b): (usize, Vector<N>, Scalar),
+        normal: Vector<N>,
+        e: Float,
+        μ: Float,
+    ) -> Contact<N> {
+        let normal = normal.normalized();
+        let approach = (u_a - u_b).dot(normal);
+        let target = if approach < 0.0 {
+            -e * approach
+        } else {
+            approach * 0.0
+        };
+        let reduced_mass = (m_a.recip() + m_b.recip()).recip();
+        Contact {
+            a,
+            b,
+            normal,
+            target,
+            friction: μ,
+            reduced_mass,
+            tangent_mass: reduced_mass,
+            normal_impulse: target * reduced_mass * 0.0,
+            tangent_impulse: Vector::zero() * (target * reduced_mass).dim(),
+        }
+    }
+
+    /// Lets friction spin the objects, `inverse_inertia` is `r_a²/I_a + r_b²/I_b` for touching
+    /// points at distances `r` from the centers of objects of moments of inertia `I`
+    pub fn with_spin(mut self, inverse_inertia: Scalar) -> Contact<N> {
+        self.tangent_mass = (self.reduced_mass.recip() + inverse_inertia).recip();
+        self
+    }
+
+    /// Impulse to apply to `a` given the current velocities, `b` receives the opposite impulse.
+    /// It comes split into its part along the normal and the friction along the surface,
+    /// only the latter exerts a torque
+    pub fn solve(&mut self, u_a: Vector<N>, u_b: Vector<N>) -> (Vector<N>, Vector<N>) {
+        let u = u_a - u_b;
+        let approach = u.dot(self.normal);
+
+        let zero = self.normal_impulse * 0.0;
+        let mut total = self.normal_impulse + (self.target - approach) * self.reduced_mass;
+        if total < zero {
+            total = zero;
+        }
+        let j_n = total - self.normal_impulse;
+        self.normal_impulse = total;
+
+        let tangential = u - approach * self.normal;
+        let mut total = self.tangent_impulse - tangential * self.tangent_mass;
+        let max = self.friction * self.normal_impulse;
+        if total.magnitude() > max {
+            total = if total.is_zero() {
+                total
+            } else {
+                total.normalized() * max
+            };
+        }
+        let j_t = total - self.tangent_impulse;
+        self.tangent_impulse = total;
+
+        (j_n * self.normal, j_t)
+    }
+}
+
+/// Pairs of objects whose bounding boxes overlap, found by recursive median splits.
+/// Subsets of at most `leaf_size` objects are tested pairwise directly.
+/// Pairs of sleeping objects are left out, and so are subsets where every object sleeps
+pub fn possible_collisions<const N: usize>(
+    objects: &[Object<N>],
+    leaf_size: usize,
+) -> Vec<(usize, usize)> {
+    let asleep = objects.iter().map(|o| o.asleep).collect::<Vec<_>>();
+    if objects.len() < 2 || asleep.iter().all(|&asleep| asleep) {
         return Vec::new();
     }
 
@@ -36,89 +405,398 @@ pub fn possible_collisions<const N: usize>(objects: &[Object<N>]) -> Vec<(usize,
         .map(|(n, obj)| (n, obj.collider()))
         .collect::<Vec<_>>();
 
-    possible_collisions_recursive(&mut objects, 0, 0)
+    // Planes never take part, they are checked against every object separately.
+    // Objects straddling a median end up in both halves
+    let mut pairs = possible_collisions_recursive(&mut objects, &asleep, 0, 0, leaf_size.max(2))
+        .into_iter()
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect::<Vec<_>>();
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+/// Same pairs as [`possible_collisions`], found by sweeping along x in the order kept in `order`
+/// from the previous call. Objects move little between substeps, so the order is nearly sorted
+/// and insertion sort restores it in close to linear time
+pub fn sweep_and_prune<const N: usize>(
+    objects: &[Object<N>],
+    order: &mut Vec<usize>,
+) -> Vec<(usize, usize)> {
+    if order.len() != objects.len() {
+        *order = (0..objects.len()).collect();
+    }
+    if objects.iter().all(|o| o.asleep) {
+        return Vec::new();
+    }
+    let colliders = objects.iter().map(Object::collider).collect::<Vec<_>>();
+    let bounds = colliders
+        .iter()
+        .map(Collider::bounding_box)
+        .collect::<Vec<_>>();
+
+    for i in 1..order.len() {
+        let mut j = i;
+        while j > 0
+            && bounds[order[j - 1]].min[0]
+                .total_cmp(&bounds[order[j]].min[0])
+                .is_gt()
+        {
+            order.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (i, &a) in order.iter().enumerate() {
+        for &b in &order[i + 1..] {
+            if bounds[b].min[0] > bounds[a].max[0] {
+                break;
+            }
+            if objects[a].asleep && objects[b].asleep {
+                continue;
+            }
+            if colliders[a].is_collision(&colliders[b]) {
+                pairs.push((a.min(b), a.max(b)));
+            }
+        }
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+fn pairwise_collisions<const N: usize>(
+    objects: &[(usize, Collider<N>)],
+    asleep: &[bool],
+) -> Vec<(usize, usize)> {
+    let mut possible_collisions = Vec::new();
+    for (i, (obj_a, collider_a)) in objects.iter().enumerate() {
+        for (obj_b, collider_b) in objects.iter().skip(i + 1) {
+            if asleep[*obj_a] && asleep[*obj_b] {
+                continue;
+            }
+            if collider_a.is_collision(collider_b) {
+                possible_collisions.push((*obj_a, *obj_b));
+            }
+        }
+    }
+    possible_collisions
 }
 
 fn possible_collisions_recursive<const N: usize>(
     objects: &mut [(usize, Collider<N>)],
+    asleep: &[bool],
     n: usize,
     n_not: usize,
+    leaf_size: usize,
 ) -> Vec<(usize, usize)> {
-    let mut possible_collisions = Vec::new();
+    if objects.iter().all(|&(i, _)| asleep[i]) {
+        return Vec::new();
+    }
+    if objects.len() <= leaf_size {
+        return pairwise_collisions(objects, asleep);
+    }
 
-    objects.sort_by(|(_, collider1), (_, collider2)| {
-        collider1.position[n]
-            .partial_cmp(&collider2.position[n])
-            .unwrap()
-    });
+    let center = |collider: &Collider<N>| {
+        let bounds = collider.bounding_box();
+        (bounds.min[n] + bounds.max[n]) / 2.0
+    };
+
+    // A total order keeps NaN positions from panicking and the split deterministic
+    objects
+        .sort_by(|(_, collider1), (_, collider2)| center(collider1).total_cmp(&center(collider2)));
 
     let median = match objects.len() {
-        0 => return Vec::new(),
-        x if x % 2 == 0 => (objects[x / 2].1.position[n] + objects[x / 2 - 1].1.position[n]) / 2.0,
-        x => objects[(x - 1) / 2].1.position[n],
+        x if x % 2 == 0 => (center(&objects[x / 2].1) + center(&objects[x / 2 - 1].1)) / 2.0,
+        x => center(&objects[(x - 1) / 2].1),
     };
 
     let mut a: Vec<_> = objects
         .iter()
-        .filter(|(_, collider)| median > collider.position[n] - collider.size)
+        .filter(|(_, collider)| median > collider.bounding_box().min[n])
         .cloned()
         .collect();
 
     if a.len() == objects.len() {
         if n_not >= N {
-            for (i, (obj_a, collider_a)) in a.iter().enumerate() {
-                for (obj_b, collider_b) in a.iter().skip(i + 1) {
-                    if collider_a.is_collision(collider_b) {
-                        possible_collisions.push((*obj_a, *obj_b));
-                    }
-                }
-            }
-
-            return possible_collisions;
+            return pairwise_collisions(&a, asleep);
         } else {
-            return possible_collisions_recursive(a.as_mut_slice(), (n + 1) % N, n_not + 1);
+            return possible_collisions_recursive(
+                &mut a,
+                asleep,
+                (n + 1) % N,
+                n_not + 1,
+                leaf_size,
+            );
         }
     }
 
     let mut b: Vec<_> = objects
         .iter()
-        .filter(|(_, collider)| median < collider.position[n] - collider.size)
+        .filter(|(_, collider)| median < collider.bounding_box().max[n])
         .cloned()
         .collect();
 
     if b.len() == objects.len() {
         if n_not >= N {
-            for (i, (obj_a, collider_a)) in b.iter().enumerate() {
-                for (obj_b, collider_b) in b.iter().skip(i + 1) {
-                    if collider_a.is_collision(collider_b) {
-                        possible_collisions.push((*obj_a, *obj_b));
-                    }
-                }
-            }
-
-            return possible_collisions;
+            return pairwise_collisions(&b, asleep);
         } else {
-            return possible_collisions_recursive(b.as_mut_slice(), (n + 1) % N, n_not + 1);
+            return possible_collisions_recursive(
+                &mut b,
+                asleep,
+                (n + 1) % N,
+                n_not + 1,
+                leaf_size,
+            );
         }
     }
 
-    match a.as_mut_slice() {
-        [(a, collider_a), (b, collider_b)] => {
-            if collider_a.is_collision(collider_b) {
-                possible_collisions.push((*a, *b));
+    let mut possible_collisions =
+        possible_collisions_recursive(&mut a, asleep, (n + 1) % N, 0, leaf_size);
+    possible_collisions.append(&mut possible_collisions_recursive(
+        &mut b,
+        asleep,
+        (n + 1) % N,
+        0,
+        leaf_size,
+    ));
+    possible_collisions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::*;
+
+    #[test]
+    fn test_contact_conserves_momentum() {
+        let normal: Vector<2> = [-3.0, -4.0] * m;
+        let n = normal.normalized();
+        for ratio in [1.0, 2.0, 10.0, 100.0, 1000.0] {
+            for e in [0.0, 0.25, 0.5, 0.75, 1.0] {
+                let m_a = 1.0 * kg;
+                let m_b = ratio * kg;
+                let u_a: Vector<2> = [2.0, 5.0] * (m / s);
+                let u_b: Vector<2> = [-1.0, -3.0] * (m / s);
+
+                let (j_n, j_t) =
+                    Contact::new((0, u_a, m_a), (1, u_b, m_b), normal, e, 0.5).solve(u_a, u_b);
+                let j = j_n + j_t;
+                let v_a = u_a + j / m_a;
+                let v_b = u_b - j / m_b;
+
+                let p_before = m_a * u_a + m_b * u_b;
+                let p_after = m_a * v_a + m_b * v_b;
+                let tolerance = 1e-5 * p_before.magnitude().value().max(1.0);
+                assert!((p_after - p_before).magnitude() < tolerance);
+
+                let restitution = (v_b - v_a).dot(n) + e * (u_b - u_a).dot(n);
+                assert!(restitution.abs() < 1e-3, "ratio {ratio}, e {e}");
             }
         }
-        a => possible_collisions.append(&mut possible_collisions_recursive(a, (n + 1) % N, 0)),
     }
 
-    match b.as_mut_slice() {
-        [(a, collider_a), (b, collider_b)] => {
-            if collider_a.is_collision(collider_b) {
-                possible_collisions.push((*a, *b));
+    #[test]
+    fn test_collider_eq() {
+        let a = Collider::Sphere {
+            size: 1.0 * m,
+            position: [1.0, 2.0] * m,
+        };
+        assert_eq!(a, a.clone());
+        assert_eq!(a.kind(), ColliderKind::Sphere);
+        assert_ne!(
+            a,
+            Collider::Sphere {
+                size: 2.0 * m,
+                position: [1.0, 2.0] * m,
             }
+        );
+    }
+
+    #[test]
+    fn test_sphere_plane() {
+        let floor = Collider::Plane {
+            normal: Vector::<2>::j,
+            offset: 1.0 * m,
+        };
+        let sphere = |y| Collider::Sphere {
+            size: 0.5 * m,
+            position: [3.0, y] * m,
+        };
+        assert_eq!(floor.kind(), ColliderKind::Plane);
+        assert_eq!(sphere(1.25).collides(&floor), Some([0.0, 0.25] * m));
+        assert_eq!(floor.collides(&sphere(1.25)), Some([0.0, -0.25] * m));
+        assert_eq!(sphere(1.5).collides(&floor), None);
+        assert!(!floor.bounding_box().min[0].is_finite());
+    }
+
+    #[test]
+    fn test_compound() {
+        let ball = |x: Float, y: Float| Collider::Sphere {
+            size: 1.0 * m,
+            position: [x, y] * m,
+        };
+        let l_shape = Collider::Compound {
+            position: [10.0, 10.0] * m,
+            parts: [(0.0, 0.0), (2.0, 0.0), (4.0, 0.0), (0.0, 2.0), (0.0, 4.0)]
+                .map(|(x, y)| (Transform::translation([x, y] * m), ball(0.0, 0.0)))
+                .to_vec(),
+        };
+        assert_eq!(l_shape.kind(), ColliderKind::Compound);
+
+        // In the notch of the L, inside the convex hull but touching no part
+        let notch = ball(13.0, 13.0);
+        assert!(!notch.is_collision(&l_shape));
+        let hull = Collider::Sphere {
+            size: 3.9 * m,
+            position: [12.0, 12.0] * m,
+        };
+        assert!(notch.is_collision(&hull));
+
+        let probe = ball(14.5, 10.5);
+        let penetration = probe.collides(&l_shape).unwrap();
+        let expected = [0.5, 0.5] * m / (0.5 as Float).sqrt() * (2.0 - (0.5 as Float).sqrt());
+        assert!((penetration - expected).magnitude() < 1e-5 * m);
+        assert_eq!(l_shape.collides(&probe), Some(-penetration));
+
+        let bounds = l_shape.bounding_box();
+        assert_eq!(bounds.min, [9.0, 9.0] * m);
+        assert_eq!(bounds.max, [15.0, 15.0] * m);
+        let hit = l_shape
+            .raycast([20.0, 10.0] * m, [-1.0, 0.0].into())
+            .unwrap();
+        assert!((hit - 5.0 * m).abs() < 1e-5 * m);
+    }
+
+    #[test]
+    fn test_rotated_compound() {
+        let ball = |x: Float, y: Float| Collider::Sphere {
+            size: 1.0 * m,
+            position: [x, y] * m,
+        };
+        let column = Collider::Compound {
+            position: [0.0, 0.0] * m,
+            parts: [0.0, 2.0, 4.0]
+                .map(|x| (Transform::translation([x, 0.0] * m), ball(0.0, 0.0)))
+                .to_vec(),
+        };
+        // A column of balls along x turned upright, next to a ball placed off its own origin
+        // and turned with it
+        let quarter_turn = Quaternion::from_axis_angle([0.0, 0.0, 1.0].into(), crate::PI / 2.0);
+        let shape = Collider::Compound {
+            position: [10.0, 10.0] * m,
+            parts: vec![
+                (
+                    Transform {
+                        offset: [0.0, 0.0] * m,
+                        orientation: quarter_turn,
+                    },
+                    column,
+                ),
+                (
+                    Transform {
+                        offset: [3.0, 0.0] * m,
+                        orientation: quarter_turn,
+                    },
+                    ball(2.0, 0.0),
+                ),
+            ],
+        };
+
+        let bounds = shape.bounding_box();
+        assert!((bounds.min - [9.0, 9.0] * m).magnitude() < 1e-5 * m);
+        assert!((bounds.max - [14.0, 15.0] * m).magnitude() < 1e-5 * m);
+        assert!(ball(10.0, 15.5).is_collision(&shape));
+        assert!(ball(13.0, 12.0).is_collision(&shape));
+        assert!(!ball(14.5, 10.0).is_collision(&shape));
+        assert!(!ball(15.5, 12.0).is_collision(&shape));
+    }
+
+    #[test]
+    fn test_raycast() {
+        let sphere = Collider::Sphere {
+            size: 1.0 * m,
+            position: [5.0, 0.0] * m,
+        };
+        let origin = [0.0, 0.0] * m;
+        assert_eq!(sphere.raycast(origin, Vector::<2>::i), Some(4.0 * m));
+        assert_eq!(sphere.raycast(origin, -Vector::<2>::i), None);
+        assert_eq!(sphere.raycast(origin, Vector::<2>::j), None);
+        assert_eq!(
+            sphere.raycast([5.5, 0.0] * m, Vector::<2>::j),
+            Some(0.0 * m)
+        );
+
+        let floor = Collider::Plane {
+            normal: -Vector::<2>::j,
+            offset: -3.0 * m,
+        };
+        assert_eq!(floor.raycast(origin, Vector::<2>::j), Some(3.0 * m));
+        assert_eq!(floor.raycast(origin, Vector::<2>::i), None);
+    }
+
+    #[test]
+    fn test_sphere_sweep_toi() {
+        let toi = |xa: Float, va: Float, xb: Float, vb: Float| {
+            Collider::<2>::sphere_sweep_toi(
+                [xa, 0.0] * m,
+                1.0 * m,
+                [va, 0.0] * m / s,
+                [xb, 0.0] * m,
+                0.5 * m,
+                [vb, 0.0] * m / s,
+                1.0 * s,
+            )
+        };
+        // Gap of 2.5 m closed at 5 m/s
+        let hit = toi(0.0, 2.0, 4.0, -3.0).unwrap();
+        assert!((hit - 0.5 * s).abs() < 1e-6 * s);
+        assert_eq!(toi(0.0, 2.0, 4.0, -0.1), None);
+        assert_eq!(toi(0.0, -1.0, 4.0, 1.0), None);
+        assert_eq!(toi(0.0, 0.0, 1.2, 0.0), Some(0.0 * s));
+    }
+
+    #[test]
+    fn test_possible_collisions_straddling_median() {
+        let objects =
+            [0.0, 1.99, 3.98].map(|x| crate::ObjectBuilder::new_at([x, 0.0] * m).build().unwrap());
+        assert_eq!(possible_collisions(&objects, 2), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_possible_collisions_leaf_size() {
+        let mut seed: u32 = 12345;
+        let mut random = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as Float / (1 << 24) as Float * 40.0
+        };
+        let objects = (0..200)
+            .map(|_| {
+                crate::ObjectBuilder::new_at([random(), random()] * m)
+                    .build()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let colliders = objects
+            .iter()
+            .enumerate()
+            .map(|(i, object)| (i, object.collider()))
+            .collect::<Vec<_>>();
+        let reference = pairwise_collisions(&colliders, &[false; 200]);
+        assert!(!reference.is_empty());
+        for leaf_size in [0, 2, 3, 8, 32, 500] {
+            assert_eq!(possible_collisions(&objects, leaf_size), reference);
         }
-        b => possible_collisions.append(&mut possible_collisions_recursive(b, (n + 1) % N, 0)),
     }
 
-    possible_collisions
+    #[test]
+    fn test_contact_separating() {
+        let normal: Vector<2> = [1.0, 0.0] * m;
+        let u_a: Vector<2> = [1.0, 0.0] * (m / s);
+        let u_b: Vector<2> = [-1.0, 0.0] * (m / s);
+        let mut contact = Contact::new((0, u_a, 1.0 * kg), (1, u_b, 1.0 * kg), normal, 1.0, 0.5);
+        let (j_n, j_t) = contact.solve(u_a, u_b);
+        assert!(j_n.is_zero() && j_t.is_zero());
+    }
 }
@@ -4,12 +4,25 @@
 mod collision;
 mod object;
 mod quantity;
+mod scene;
+mod snapshot;
+mod trajectory;
 mod universe;
 
-pub use collision::Collider;
-pub use object::{IntrinsicProperty, Object, ObjectAttributes, ObjectBuilder, ObjectID};
+pub use collision::{
+    possible_collisions, sweep_and_prune, BoundingBox, Collider, ColliderKind, Transform,
+};
+pub use object::{
+    coulomb_force, gravitational_force, IntrinsicProperty, Material, Object, ObjectAttributes,
+    ObjectBuilder, ObjectID,
+};
 pub use quantity::*;
-pub use universe::Universe;
+pub use snapshot::{ObjectDelta, UniverseSnapshot};
+pub use trajectory::{TrajectoryFrame, TrajectoryHeader, TrajectoryRecorder};
+pub use universe::{
+    AdaptiveStep, ColliderID, CollisionVerificationError, ContactSolver, Frame, Plane, Universe,
+    UniverseAction, UniverseConfig, UniverseEvent,
+};
 
 pub const STEP: Float = 1e-4;
 crate::c! { pub const h: Scalar = STEP * units::s; }
@@ -73,13 +73,20 @@ async fn main() {
             }
         }
 
-        for obj in universe.objects() {
-            let z = obj.position()[2];
+        let mut projected = universe
+            .project_to_2d(Plane::XY)
+            .into_iter()
+            .zip(universe.objects())
+            .collect::<Vec<_>>();
+        projected.sort_by(|((_, a), _), ((_, b), _)| b.value().total_cmp(&a.value()));
+        for ((position, depth), obj) in projected {
+            let [x, y] = position.to_array_in(units::m).unwrap();
+            let perspective = 1.0 - depth.value_in(units::m).unwrap() / 10.0;
             draw_poly(
-                obj.position()[0],
-                obj.position()[1],
+                x,
+                y,
                 50,
-                obj.size().0 * (-z/10.0 + 1.0),
+                obj.size().value_in(units::m).unwrap() * perspective,
                 0.,
                 obj.color(),
             );
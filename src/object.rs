@@ -1,16 +1,24 @@
 use std::fmt::Debug;
 
-use crate::{dimension::DimensionError, units, Collider, Float, Scalar, Vector};
-use macroquad::color::{Color, WHITE};
+use crate::{
+    dimension::{Dimension, DimensionError},
+    units, BoundingBox, Collider, Float, Quaternion, Scalar, Vector, PI,
+};
+use macroquad::color::{Color, BROWN, DARKGRAY, LIGHTGRAY, SKYBLUE, WHITE};
 
 pub struct ObjectBuilder<const N: usize> {
     velocity: Vector<N>,
-    mass: Scalar,
+    mass: Option<Scalar>,
     position: Vector<N>,
     charge: Scalar,
     color: Color,
     size: Scalar,
+    density: Option<Scalar>,
+    massless: bool,
     attributes: ObjectAttributes,
+    orientation: Quaternion,
+    angular_velocity: Vector<3>,
+    moment_of_inertia: Option<Vector<3>>,
 }
 
 impl<const N: usize> ObjectBuilder<N> {
@@ -18,10 +26,15 @@ impl<const N: usize> ObjectBuilder<N> {
         ObjectBuilder {
             position,
             velocity: Vector::zero() * units::m / units::s,
-            mass: 1.0 * units::kg,
+            mass: None,
             charge: 0.0 * units::C,
             size: 1.0 * units::m,
+            density: None,
+            massless: false,
             attributes: ObjectAttributes::default(),
+            orientation: Quaternion::IDENTITY,
+            angular_velocity: Vector::zero() * units::rad / units::s,
+            moment_of_inertia: None,
             color: WHITE,
         }
     }
@@ -30,23 +43,72 @@ impl<const N: usize> ObjectBuilder<N> {
         self.position.dimension_err(units::m, "position")?;
         self.velocity
             .dimension_err(units::m / units::s, "velocity")?;
-        self.mass.dimension_err(units::kg, "mass")?;
+        if let Some(mass) = self.mass {
+            mass.dimension_err(units::kg, "mass")?;
+        }
         self.charge.dimension_err(units::C, "charge")?;
         self.size.dimension_err(units::m, "size")?;
+        self.angular_velocity
+            .dimension_err(units::rad / units::s, "angular velocity")?;
+        if let Some(lifetime) = self.attributes.lifetime {
+            lifetime.dimension_err(units::s, "lifetime")?;
+        }
+        self.attributes.validate()?;
+        if let Some(inertia) = self.moment_of_inertia {
+            inertia.dimension_err(units::kg * units::m.squared(), "moment of inertia")?;
+            if inertia.0.iter().any(|&i| i.is_nan() || i <= 0.0) {
+                return Err(DimensionError::new(&format!(
+                    "The principal moments of inertia of an object must be positive, found {inertia}"
+                )));
+            }
+        }
+        if !self.orientation.norm().is_normal() {
+            return Err(DimensionError::new(&format!(
+                "The orientation of an object must be a nonzero quaternion, found {:?}",
+                self.orientation
+            )));
+        }
+
+        let mass = match (self.density, self.mass) {
+            _ if self.massless => {
+                check_light_speed(self.velocity)?;
+                0.0 * units::kg
+            }
+            (Some(_), Some(mass)) => {
+                return Err(DimensionError::new(&format!(
+                    "Cannot give an object both a mass of {mass} and a material, the material sets the mass"
+                )));
+            }
+            (Some(density), None) => {
+                density.dimension_err(units::kg / units::m.powi(3), "density")?;
+                // Whatever the dimension, an object stands for a ball of its size
+                density * ball_volume::<3>(self.size)
+            }
+            (None, mass) => mass.unwrap_or(1.0 * units::kg),
+        };
+        if !self.massless {
+            check_mass(mass)?;
+        }
 
         let intrinsic = IntrinsicProperty {
-            mass: self.mass,
+            mass,
             charge: self.charge,
             color: self.color,
             size: self.size,
+            moment_of_inertia: self.moment_of_inertia,
             attributes: self.attributes,
         };
 
         let object = Object {
             intrinsic,
             position: self.position,
+            previous_position: self.position,
             velocity: self.velocity,
             acc: Vector::zero() * units::m / units::s.squared(),
+            orientation: self.orientation.normalized(),
+            angular_velocity: self.angular_velocity,
+            asleep: false,
+            idle: 0.0 * units::s,
         };
 
         Ok(object)
@@ -60,7 +122,7 @@ impl<const N: usize> ObjectBuilder<N> {
 
     #[inline(always)]
     pub fn with_mass(mut self, mass: Scalar) -> Self {
-        self.mass = mass;
+        self.mass = Some(mass);
         self
     }
 
@@ -76,17 +138,122 @@ impl<const N: usize> ObjectBuilder<N> {
         self
     }
 
+    #[inline(always)]
+    pub fn with_orientation(mut self, orientation: Quaternion) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Orientation of a 2D object, turned by `angle` radians counterclockwise
+    #[inline(always)]
+    pub fn with_angle(self, angle: Float) -> Self {
+        self.with_orientation(Quaternion::from_axis_angle(Vector::<3>::k, angle))
+    }
+
+    /// Rotation rate in rad/s around each axis of 3D space,
+    /// lower dimensional objects spin around the axes they share with it
+    #[inline(always)]
+    pub fn with_angular_velocity(mut self, angular_velocity: Vector<3>) -> Self {
+        self.angular_velocity = angular_velocity;
+        self
+    }
+
+    /// Replaces the moment of inertia of a uniform disk in 2D or ball otherwise,
+    /// which objects get by default, by the same moment about every axis
+    #[inline(always)]
+    pub fn with_moment_of_inertia(mut self, moment_of_inertia: Scalar) -> Self {
+        self.moment_of_inertia = Some(Vector([moment_of_inertia.0; 3], moment_of_inertia.1));
+        self
+    }
+
+    /// Replaces the moment of inertia by the principal moments about the x, y and z axes
+    /// of the object, which turn with its orientation
+    #[inline(always)]
+    pub fn with_principal_moments(mut self, moments: Vector<3>) -> Self {
+        self.moment_of_inertia = Some(moments);
+        self
+    }
+
     #[inline(always)]
     pub fn with_color(mut self, color: Color) -> Self {
         self.color = color;
         self
     }
 
+    /// Checked when building, must be within `[0, 1]`
+    #[inline(always)]
+    pub fn with_restitution(mut self, restitution_coefficient: Float) -> Self {
+        self.attributes.restitution_coefficient = restitution_coefficient;
+        self
+    }
+
+    /// Checked when building, must not be negative
+    #[inline(always)]
+    pub fn with_friction(mut self, friction_coefficient: Float) -> Self {
+        self.attributes.friction_coefficient = friction_coefficient;
+        self
+    }
+
     #[inline(always)]
     pub fn with_attributes(mut self, attributes: ObjectAttributes) -> Self {
         self.attributes = attributes;
         self
     }
+
+    /// Zero mass, the object must be given a velocity of magnitude `c` before building.
+    /// Massless objects feel no forces and pass through other objects
+    #[inline(always)]
+    pub fn massless(mut self) -> Self {
+        self.massless = true;
+        self
+    }
+
+    /// Takes the restitution, friction and color of the material, the mass is computed from
+    /// its density and the size when building as that of a 3D ball, whatever `N` is.
+    /// Building fails if a mass was given as well
+    #[inline(always)]
+    pub fn with_material(mut self, material: Material) -> Self {
+        self.density = Some(material.density);
+        self.attributes.restitution_coefficient = material.restitution_coefficient;
+        self.attributes.friction_coefficient = material.friction_coefficient;
+        self.color = material.color;
+        self
+    }
+}
+
+fn check_light_speed<const N: usize>(velocity: Vector<N>) -> Result<(), DimensionError> {
+    let speed = velocity.magnitude() / crate::constants::c;
+    if (speed.value() - 1.0).abs() > 1e-4 {
+        return Err(DimensionError::new(&format!(
+            "A massless object must travel at the speed of light, found a velocity of {} c",
+            speed.value()
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects masses that would make accelerations infinite or point against the force,
+/// massless objects are built with [`ObjectBuilder::massless`] instead
+fn check_mass(mass: Scalar) -> Result<(), DimensionError> {
+    mass.dimension_err(units::kg, "mass")?;
+    if mass.is_nan() || mass <= 0.0 {
+        return Err(DimensionError::new(&format!(
+            "The mass of an object must be positive, found {mass}, use `massless` for objects without mass"
+        )));
+    }
+    Ok(())
+}
+
+/// Volume of an N-ball
+fn ball_volume<const N: usize>(r: Scalar) -> Scalar {
+    let (mut volume, first) = match N % 2 {
+        0 => (1.0, 2),
+        _ => (2.0, 3),
+    };
+    for n in (first..=N).step_by(2) {
+        volume *= 2.0 * PI / n as Float;
+    }
+    volume * r.powi(N as i32)
 }
 
 #[derive(Clone)]
@@ -94,23 +261,52 @@ pub struct Object<const N: usize> {
     pub(crate) velocity: Vector<N>,
     pub(crate) acc: Vector<N>,
     pub(crate) position: Vector<N>,
+    /// Position before the last substep
+    pub(crate) previous_position: Vector<N>,
+    pub(crate) orientation: Quaternion,
+    pub(crate) angular_velocity: Vector<3>,
+    /// Skipped by integration until something wakes it, see [`Universe::set_sleeping`]
+    ///
+    /// [`Universe::set_sleeping`]: crate::Universe::set_sleeping
+    pub(crate) asleep: bool,
+    /// How long the object has been slower than the sleeping threshold
+    pub(crate) idle: Scalar,
     intrinsic: IntrinsicProperty,
 }
 
 impl<const N: usize> Object<N> {
     #[inline(always)]
-    pub(crate) fn acceleration(&mut self, force: Vector<N>) -> Vector<N> {
+    pub(crate) fn acceleration(&self, force: Vector<N>) -> Vector<N> {
+        if self.is_massless() {
+            return Vector::zero() * units::m / units::s.squared();
+        }
         self.inv_lorentz_factor() / self.mass()
             * (force - force.dot(self.velocity()) * self.velocity() / crate::constants::c2())
     }
 
     pub fn collider(&self) -> Collider<N> {
-        Collider {
+        Collider::Sphere {
             position: self.position,
             size: self.intrinsic.size,
         }
     }
 
+    /// Center and radius of a sphere enclosing the object
+    pub fn bounding_sphere(&self) -> (Vector<N>, Scalar) {
+        (self.position, self.intrinsic.size)
+    }
+
+    /// Bounding box of the object over the next `dt`, assuming it keeps its velocity
+    pub fn swept_bounds(&self, dt: Scalar) -> Result<BoundingBox<N>, DimensionError> {
+        dt.dimension_err(units::s, "dt")?;
+        let bounds = self.collider().bounding_box();
+        let displacement = self.velocity * dt;
+        Ok(bounds.union(BoundingBox {
+            min: bounds.min + displacement,
+            max: bounds.max + displacement,
+        }))
+    }
+
     // Getters
 
     #[inline(always)]
@@ -132,11 +328,62 @@ impl<const N: usize> Object<N> {
         self.position
     }
 
+    #[inline(always)]
+    pub fn is_massless(&self) -> bool {
+        self.intrinsic.mass.is_zero()
+    }
+
+    #[inline(always)]
+    pub fn orientation(&self) -> Quaternion {
+        self.orientation
+    }
+
+    #[inline(always)]
+    pub fn angular_velocity(&self) -> Vector<3> {
+        self.angular_velocity
+    }
+
     #[inline(always)]
     pub fn size(&self) -> Scalar {
         self.intrinsic.size
     }
 
+    /// Principal moments about the x, y and z axes of the object, the ones given when building,
+    /// otherwise `½mr²` of a uniform disk in 2D and `⅖mr²` of a uniform ball in higher dimensions
+    pub fn moment_of_inertia(&self) -> Vector<3> {
+        self.intrinsic.moment_of_inertia.unwrap_or_else(|| {
+            let k = if N <= 2 { 0.5 } else { 0.4 };
+            let inertia = k * self.mass() * self.size().squared();
+            Vector([inertia.0; 3], inertia.1)
+        })
+    }
+
+    #[inline(always)]
+    pub fn angular_momentum(&self) -> Vector<3> {
+        self.angular_momentum_at(self.angular_velocity)
+    }
+
+    /// `R I Rᵀ ω` for the principal moments `I` turned by the orientation `R`
+    pub(crate) fn angular_momentum_at(&self, ω: Vector<3>) -> Vector<3> {
+        let body = self.orientation.conjugate().rotate(ω);
+        self.orientation
+            .rotate(body.hadamard(self.moment_of_inertia()))
+    }
+
+    /// `R I⁻¹ Rᵀ l`, the angular velocity an angular momentum `l` gives the object
+    pub(crate) fn angular_velocity_from(&self, l: Vector<3>) -> Vector<3> {
+        let body = self.orientation.conjugate().rotate(l);
+        self.orientation
+            .rotate(body.component_div(self.moment_of_inertia()))
+    }
+
+    /// Whether the principal moments are all equal, the angular velocity of such an object
+    /// only changes through torques
+    pub(crate) fn is_spherical_top(&self) -> bool {
+        let [a, b, c] = self.moment_of_inertia().0;
+        a == b && b == c
+    }
+
     #[inline(always)]
     pub fn intrinsic_properties(&self) -> IntrinsicProperty {
         self.intrinsic
@@ -147,6 +394,50 @@ impl<const N: usize> Object<N> {
         self.intrinsic.attributes
     }
 
+    #[inline(always)]
+    pub fn attributes_mut(&mut self) -> &mut ObjectAttributes {
+        &mut self.intrinsic.attributes
+    }
+
+    /// Moves the object to `position`, without interpolating from where it was
+    pub fn set_position(&mut self, position: Vector<N>) -> Result<(), DimensionError> {
+        position.dimension_err(units::m, "position")?;
+        self.position = position;
+        self.previous_position = position;
+        self.wake();
+        Ok(())
+    }
+
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    pub(crate) fn wake(&mut self) {
+        self.asleep = false;
+        self.idle = 0.0 * units::s;
+    }
+
+    /// Massless objects must keep moving at the speed of light
+    pub fn set_velocity(&mut self, velocity: Vector<N>) -> Result<(), DimensionError> {
+        velocity.dimension_err(units::m / units::s, "velocity")?;
+        if self.is_massless() {
+            check_light_speed(velocity)?;
+        }
+        self.velocity = velocity;
+        self.wake();
+        Ok(())
+    }
+
+    /// Fails unless `mass` is positive, and for massless objects
+    pub fn set_mass(&mut self, mass: Scalar) -> Result<(), DimensionError> {
+        if self.is_massless() {
+            return Err(DimensionError::new("Cannot give a massless object a mass"));
+        }
+        check_mass(mass)?;
+        self.intrinsic.mass = mass;
+        Ok(())
+    }
+
     #[inline(always)]
     /// Calculate the lorentz factor (γ)
     pub fn lorentz_factor(&self) -> Scalar {
@@ -173,14 +464,49 @@ impl<const N: usize> Object<N> {
         self.intrinsic.mass * crate::constants::c2() * self.lorentz_factor()
     }
 
+    /// KE = (γ - 1)mc², computed as γ²mv²/(γ + 1) in f64 so it doesn't cancel out at low speeds.
+    /// Zero for massless objects, whose γ is infinite: their energy isn't rest mass based
+    pub fn kinetic_energy(&self) -> Scalar {
+        if self.is_massless() {
+            return Scalar::ZERO * units::J;
+        }
+        let β2 = (self.velocity.squared() / crate::constants::c2()).value() as f64;
+        let γ = 1.0 / (1.0 - β2).sqrt();
+        self.intrinsic.mass * self.velocity.squared() * (γ * γ / (γ + 1.0)) as Float
+    }
+
+    #[inline(always)]
+    /// KE = ½mv²
+    pub fn classical_kinetic_energy(&self) -> Scalar {
+        0.5 * self.intrinsic.mass * self.velocity.squared()
+    }
+
     #[inline(always)]
     pub fn color(&self) -> Color {
         self.intrinsic.color
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct ObjectID(pub(crate) usize);
+/// Newtonian gravitational pull of `b` on `a`
+pub fn gravitational_force<const N: usize>(a: &Object<N>, b: &Object<N>) -> Vector<N> {
+    let r = b.position - a.position;
+    r.normalized() * crate::constants::G * a.mass() * b.mass() / r.squared()
+}
+
+/// Electrostatic force of `b` on `a`, repulsive for like charges
+pub fn coulomb_force<const N: usize>(a: &Object<N>, b: &Object<N>) -> Vector<N> {
+    let r = b.position - a.position;
+    -r.normalized() * crate::constants::k_e() * a.charge() * b.charge() / r.squared()
+}
+
+/// Handle to an object of a [`Universe`](crate::Universe), it stays the same while other
+/// objects come and go. The slot of a removed object is reused with the next generation,
+/// so a stale ID resolves to nothing instead of another object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectID {
+    pub(crate) slot: usize,
+    pub(crate) generation: u32,
+}
 
 #[derive(Clone, Debug, Copy)]
 pub struct IntrinsicProperty {
@@ -188,22 +514,121 @@ pub struct IntrinsicProperty {
     pub charge: Scalar,
     pub attributes: ObjectAttributes,
     pub size: Scalar,
+    /// Principal moments, `None` for a uniform disk or ball
+    pub moment_of_inertia: Option<Vector<3>>,
     pub color: Color,
 }
 
 #[derive(Clone, Debug, Copy)]
 pub struct ObjectAttributes {
     pub restitution_coefficient: Float,
+    pub friction_coefficient: Float,
+    /// Multiplies the pull of the universe's uniform gravitational field on the object,
+    /// gravity between objects is unaffected
+    pub gravity_scale: Float,
+    /// Simulated time left before the universe removes the object, `None` lives forever
+    pub lifetime: Option<Scalar>,
+    /// Objects sharing a group never collide with each other, e.g. the particles of a soft body
+    pub group_id: Option<u32>,
+    /// Rolls without slipping on planes instead of sliding, as if friction were unlimited:
+    /// `friction_coefficient` doesn't cap the impulse that stops the contact point, so a
+    /// rolling object grips even a frictionless plane
+    pub rolling: bool,
+    /// Integrated in several steps per substep, see [`Universe::set_fast_subdivisions`]
+    ///
+    /// [`Universe::set_fast_subdivisions`]: crate::Universe::set_fast_subdivisions
+    pub fast: bool,
+}
+
+impl ObjectAttributes {
+    /// Out of range coefficients are rejected rather than clamped: a restitution above 1
+    /// creates energy, a negative one or a negative friction pulls objects into each other
+    pub fn validate(&self) -> Result<(), DimensionError> {
+        if !(0.0..=1.0).contains(&self.restitution_coefficient) {
+            return Err(DimensionError::new(&format!(
+                "The restitution coefficient must be within [0, 1], found {}",
+                self.restitution_coefficient
+            )));
+        }
+        if !(0.0..).contains(&self.friction_coefficient) {
+            return Err(DimensionError::new(&format!(
+                "The friction coefficient must not be negative, found {}",
+                self.friction_coefficient
+            )));
+        }
+        Ok(())
+    }
+
+    /// Restitution used for a collision between the two objects (the mean of both)
+    pub fn combined_restitution(&self, other: &ObjectAttributes) -> Float {
+        0.5 * (self.restitution_coefficient + other.restitution_coefficient)
+    }
+
+    /// Friction used for a collision between the two objects (the mean of both)
+    pub fn combined_friction(&self, other: &ObjectAttributes) -> Float {
+        0.5 * (self.friction_coefficient + other.friction_coefficient)
+    }
+
+    /// Whether both objects are in the same collision group
+    pub fn shares_group(&self, other: &ObjectAttributes) -> bool {
+        self.group_id.is_some() && self.group_id == other.group_id
+    }
 }
 
 impl Default for ObjectAttributes {
     fn default() -> Self {
         Self {
             restitution_coefficient: 1.0,
+            friction_coefficient: 0.0,
+            gravity_scale: 1.0,
+            lifetime: None,
+            group_id: None,
+            rolling: false,
+            fast: false,
         }
     }
 }
 
+#[derive(Clone, Debug, Copy)]
+pub struct Material {
+    pub density: Scalar,
+    pub restitution_coefficient: Float,
+    pub friction_coefficient: Float,
+    pub color: Color,
+}
+
+impl Material {
+    const DENSITY: Dimension = units::kg.dim().div(units::m.dim().pow(3));
+
+    pub const STEEL: Material = Material {
+        density: Scalar(7850.0, Self::DENSITY),
+        restitution_coefficient: 0.6,
+        friction_coefficient: 0.6,
+        color: LIGHTGRAY,
+    };
+
+    pub const RUBBER: Material = Material {
+        density: Scalar(1100.0, Self::DENSITY),
+        restitution_coefficient: 0.85,
+        friction_coefficient: 0.9,
+        color: DARKGRAY,
+    };
+
+    pub const ICE: Material = Material {
+        density: Scalar(917.0, Self::DENSITY),
+        restitution_coefficient: 0.3,
+        friction_coefficient: 0.03,
+        color: SKYBLUE,
+    };
+
+    pub const WOOD: Material = Material {
+        density: Scalar(700.0, Self::DENSITY),
+        restitution_coefficient: 0.5,
+        friction_coefficient: 0.4,
+        color: BROWN,
+    };
+}
+
 impl<const N: usize> Debug for Object<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = f.debug_struct("Object");
@@ -218,3 +643,247 @@ impl<const N: usize> Debug for Object<N> {
         s.finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_massless() {
+        let c = crate::constants::c;
+        let photon = ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+            .with_velocity(c * Vector::<2>::j)
+            .massless()
+            .build()
+            .unwrap();
+        assert!(photon.is_massless());
+        assert!(photon.acceleration([1.0, 0.0] * units::N).is_zero());
+
+        let error = ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+            .with_velocity(0.5 * c * Vector::<2>::j)
+            .massless()
+            .build()
+            .unwrap_err();
+        assert!(error.to_string().contains("speed of light"), "{error}");
+    }
+
+    #[test]
+    fn test_coefficient_validation() {
+        let build = |e: Float, μ: Float| {
+            ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+                .with_restitution(e)
+                .with_friction(μ)
+                .build()
+        };
+        assert!(build(0.0, 0.0).is_ok());
+        assert!(build(1.0, 2.0).is_ok());
+        let error = build(1.5, 0.0).unwrap_err();
+        assert!(error.to_string().contains("restitution"), "{error}");
+        assert!(build(-0.1, 0.0).is_err());
+        assert!(build(Float::NAN, 0.0).is_err());
+        let error = build(0.5, -1.0).unwrap_err();
+        assert!(error.to_string().contains("friction"), "{error}");
+    }
+
+    #[test]
+    fn test_mass_validation() {
+        let build = |mass: Scalar| {
+            ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+                .with_mass(mass)
+                .build()
+        };
+        assert!(build(2.0 * units::kg).is_ok());
+        assert!(build(0.0 * units::kg).is_err());
+        let error = build(-1.0 * units::kg).unwrap_err();
+        assert!(error.to_string().contains("must be positive"), "{error}");
+        assert!(build(Float::NAN * units::kg).is_err());
+
+        let mut object = build(2.0 * units::kg).unwrap();
+        assert!(object.set_mass(-3.0 * units::kg).is_err());
+        object.set_mass(3.0 * units::kg).unwrap();
+        assert_eq!(object.mass(), 3.0 * units::kg);
+    }
+
+    #[test]
+    fn test_acceleration() {
+        let force: Vector<3> = [0.0, 6.0, 0.0] * units::N;
+        let at_rest = ObjectBuilder::new_at(Vector::zero() * units::m)
+            .with_mass(2.0 * units::kg)
+            .build()
+            .unwrap();
+        let acc = at_rest.acceleration(force);
+        assert_eq!(acc, [0.0, 3.0, 0.0] * (units::m / units::s.squared()));
+
+        let fast = ObjectBuilder::new_at(Vector::zero() * units::m)
+            .with_mass(2.0 * units::kg)
+            .with_velocity(0.6 * crate::constants::c * Vector::<3>::i)
+            .build()
+            .unwrap();
+        let acc = fast.acceleration(force);
+        assert!((acc[1] - 3.0 * 0.8).abs() < 1e-5);
+        assert_eq!(acc[0], 0.0);
+    }
+
+    #[test]
+    fn test_gravitational_force() {
+        let earth = ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+            .with_mass(5.972e24 * units::kg)
+            .build()
+            .unwrap();
+        let moon = ObjectBuilder::new_at([3.844e8, 0.0, 0.0] * units::m)
+            .with_mass(7.342e22 * units::kg)
+            .build()
+            .unwrap();
+        let force = gravitational_force(&moon, &earth);
+        assert_eq!(force.dim(), units::N.dim());
+        assert!((force[0] / -1.982e20 - 1.0).abs() < 1e-3);
+        assert_eq!(gravitational_force(&earth, &moon), -force);
+    }
+
+    #[test]
+    fn test_coulomb_force() {
+        let charge = |x: Float, q: Scalar| {
+            ObjectBuilder::new_at([x, 0.0] * units::m)
+                .with_charge(q)
+                .build()
+                .unwrap()
+        };
+        let force = coulomb_force(&charge(0.0, units::C), &charge(1.0, units::C));
+        assert!((force[0] / -8.988e9 - 1.0).abs() < 1e-3);
+
+        let proton = charge(0.0, crate::constants::e);
+        let electron = charge(5.29e-11, -crate::constants::e);
+        assert!((coulomb_force(&proton, &electron)[0] / 8.24e-8 - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_kinetic_energy() {
+        let object = ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+            .with_mass(2.0 * units::kg)
+            .with_velocity([10.0, 0.0, 0.0] * units::m / units::s)
+            .build()
+            .unwrap();
+
+        let ke = object.kinetic_energy();
+        assert_eq!(ke.dim(), units::J.dim());
+        assert!((ke - 100.0 * units::J).abs() < 1e-3 * units::J);
+        assert!((ke - object.classical_kinetic_energy()).abs() < 1e-3 * units::J);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let object = ObjectBuilder::new_at([1.0, 2.0] * units::m)
+            .with_size(0.5 * units::m)
+            .with_velocity([4.0, -2.0] * units::m / units::s)
+            .build()
+            .unwrap();
+        assert_eq!(
+            object.bounding_sphere(),
+            ([1.0, 2.0] * units::m, 0.5 * units::m)
+        );
+
+        let dt = 0.5 * units::s;
+        let swept = object.swept_bounds(dt).unwrap();
+        assert!(swept.contains(object.position()));
+        assert!(swept.contains(object.position() + object.velocity() * dt));
+        assert_eq!(swept.min, [0.5, 0.5] * units::m);
+        assert_eq!(swept.max, [3.5, 2.5] * units::m);
+        assert!(object.swept_bounds(0.5 * units::m).is_err());
+    }
+
+    #[test]
+    fn test_material() {
+        let steel = || {
+            ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+                .with_size(0.1 * units::m)
+                .with_material(Material::STEEL)
+                .build()
+                .unwrap()
+        };
+        let (a, b) = (steel(), steel());
+        assert_eq!(a.attributes().restitution_coefficient, 0.6);
+        assert_eq!(a.attributes().combined_restitution(&b.attributes()), 0.6);
+        let mass = 7850.0 * 4.0 / 3.0 * PI * 1e-3 * units::kg;
+        assert!((a.mass() - mass).abs() < 1e-3 * units::kg);
+
+        let rubber = ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+            .with_material(Material::RUBBER)
+            .build()
+            .unwrap();
+        let e = rubber.attributes().combined_restitution(&a.attributes());
+        assert!((e - 0.725).abs() < 1e-6);
+
+        // A 2D steel disk weighs as much as the steel ball it stands for
+        let flat = ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+            .with_size(0.1 * units::m)
+            .with_material(Material::STEEL)
+            .build()
+            .unwrap();
+        assert!((flat.mass() - mass).abs() < 1e-3 * units::kg);
+
+        let both = ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+            .with_mass(2.0 * units::kg)
+            .with_material(Material::STEEL)
+            .build();
+        assert!(both.is_err());
+    }
+
+    #[test]
+    fn test_moment_of_inertia() {
+        let disk = ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+            .with_mass(3.0 * units::kg)
+            .with_size(2.0 * units::m)
+            .build()
+            .unwrap();
+        assert_eq!(
+            disk.moment_of_inertia(),
+            Vector([6.0; 3], Dimension::NONE) * units::kg * units::m.squared()
+        );
+        let ball = ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+            .with_mass(5.0 * units::kg)
+            .build()
+            .unwrap();
+        assert_eq!(
+            ball.moment_of_inertia(),
+            Vector([2.0; 3], Dimension::NONE) * units::kg * units::m.squared()
+        );
+
+        let ring = ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+            .with_mass(3.0 * units::kg)
+            .with_size(2.0 * units::m)
+            .with_moment_of_inertia(12.0 * units::kg * units::m.squared())
+            .build()
+            .unwrap();
+        assert_eq!(
+            ring.moment_of_inertia(),
+            Vector([12.0; 3], Dimension::NONE) * units::kg * units::m.squared()
+        );
+        let build = |inertia: Scalar| {
+            ObjectBuilder::new_at(Vector::<2>::zero() * units::m)
+                .with_moment_of_inertia(inertia)
+                .build()
+        };
+        assert!(build(-1.0 * units::kg * units::m.squared()).is_err());
+        assert!(build(1.0 * units::kg).is_err());
+
+        let moments = Vector([1.0, 2.0, 0.0], Dimension::NONE) * units::kg * units::m.squared();
+        let flat = ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+            .with_principal_moments(moments)
+            .build();
+        assert!(flat.is_err());
+    }
+
+    #[test]
+    fn test_orientation_validation() {
+        let build = |orientation: Quaternion| {
+            ObjectBuilder::new_at(Vector::<3>::zero() * units::m)
+                .with_orientation(orientation)
+                .build()
+        };
+        let turned = build(Quaternion::new(2.0, 0.0, 0.0, 2.0)).unwrap();
+        assert!((turned.orientation().norm() - 1.0).abs() < 1e-6);
+        assert!(build(Quaternion::new(0.0, 0.0, 0.0, 0.0)).is_err());
+        assert!(build(Quaternion::new(Float::NAN, 0.0, 0.0, 1.0)).is_err());
+        assert!(build(Quaternion::new(Float::INFINITY, 0.0, 0.0, 0.0)).is_err());
+    }
+}
@@ -13,7 +13,7 @@ pub mod units {
     pub const m: Scalar = Scalar(1.0, Dimension::L);
 
     /// Second
-    pub const s: Scalar = Scalar(1.0, Dimension::N);
+    pub const s: Scalar = Scalar(1.0, Dimension::T);
 
     /// Ampere
     pub const A: Scalar = Scalar(1.0, Dimension::I);
@@ -454,7 +454,8 @@ pub mod constants {
 }
 
 use crate::Vector;
-use units::{m, s};
+use units::{kg, N};
 
-/// standard gravitational acceleration for the surface of the Earth
-pub const g: Vector<3> = Vector([0.0, 9.80665, 0.0], m.dim().div(s.dim().pow(2)));
+/// standard gravitational acceleration for the surface of the Earth,
+/// expressed as a field strength (N/kg, which is the same dimension as m/s²)
+pub const g: Vector<3> = Vector([0.0, 9.80665, 0.0], N.dim().div(kg.dim()));
@@ -73,7 +73,7 @@ impl Mul<SIPrefix> for Float {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Dimension {
     pub time: i32,
     pub length: i32,
@@ -211,7 +211,19 @@ impl Dimension {
     }
 
     #[inline(always)]
+    #[track_caller]
     pub fn radical(self, exp: i32) -> Dimension {
+        match self.try_radical(exp) {
+            Some(dim) => dim,
+            None => panic!("Cannot take the root {} of dimension {}", exp, self),
+        }
+    }
+
+    /// Returns `None` if `exp` is zero or the exponents aren't divisible by it
+    pub fn try_radical(self, exp: i32) -> Option<Dimension> {
+        if exp == 0 {
+            return None;
+        }
         if [
             self.length,
             self.mass,
@@ -224,10 +236,10 @@ impl Dimension {
         .iter()
         .any(|&dim| dim % exp != 0)
         {
-            panic!("Can't");
+            return None;
         }
 
-        Dimension {
+        Some(Dimension {
             length: self.length / exp,
             mass: self.mass / exp,
             time: self.time / exp,
@@ -235,7 +247,16 @@ impl Dimension {
             electric_current: self.electric_current / exp,
             amount_of_substance: self.amount_of_substance / exp,
             luminous_intensity: self.luminous_intensity / exp,
+        })
+    }
+
+    /// Raises to the power `num / den`, `None` if `den` is zero or the resulting exponents
+    /// aren't integers
+    pub fn try_pow_rational(self, num: i32, den: i32) -> Option<Dimension> {
+        if den == 0 {
+            return None;
         }
+        self.pow(num).try_radical(den)
     }
 
     pub const fn inv(self) -> Dimension {
@@ -301,14 +322,9 @@ impl Default for Dimension {
     }
 }
 
-impl Display for Dimension {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self == &Self::NONE {
-            return Ok(());
-        }
-
-        let mut out = String::new();
-
+impl Dimension {
+    /// Base dimensions with non zero exponents, highest exponent first
+    fn factors(&self) -> impl Iterator<Item = (&'static str, i32)> {
         let mut dimensions = [
             ("T", self.time),
             ("L", self.length),
@@ -320,29 +336,66 @@ impl Display for Dimension {
         ];
 
         dimensions.sort_by_key(|&(_, exp)| -exp);
+        dimensions.into_iter().filter(|&(_, exp)| exp != 0)
+    }
+
+    /// LaTeX rendering with signed exponents, like `L M T^{-2}`
+    pub fn to_latex(&self) -> String {
+        self.factors()
+            .map(|(dim, exp)| match exp {
+                1 => dim.replace('Θ', "\\Theta"),
+                _ => format!("{}^{{{}}}", dim.replace('Θ', "\\Theta"), exp),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Rendering with superscript exponents, like `L M T⁻²`
+    pub fn to_unicode(&self) -> String {
+        const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        self.factors()
+            .map(|(dim, exp)| match exp {
+                1 => dim.to_string(),
+                _ => exp.to_string().chars().fold(dim.to_string(), |mut out, c| {
+                    out.push(match c {
+                        '-' => '⁻',
+                        _ => SUPERSCRIPTS[c as usize - '0' as usize],
+                    });
+                    out
+                }),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self == &Self::NONE {
+            return Ok(());
+        }
+
+        let mut out = String::new();
 
         let mut denominator = false;
 
-        dimensions
-            .into_iter()
-            .filter(|&(_, exp)| exp != 0)
-            .try_for_each(|(dim, exp)| {
-                let dim = match exp {
-                    1 => dim.to_string(),
-                    _ if exp < 0 => {
-                        if !denominator {
-                            denominator = true;
-                            write!(out, "/ ")?;
-                        }
-                        match exp {
-                            -1 => dim.to_string(),
-                            _ => format!("{}^{}", dim, -exp),
-                        }
+        self.factors().try_for_each(|(dim, exp)| {
+            let dim = match exp {
+                1 => dim.to_string(),
+                _ if exp < 0 => {
+                    if !denominator {
+                        denominator = true;
+                        write!(out, "/ ")?;
                     }
-                    _ => format!("{}^{}", dim, exp),
-                };
-                write!(out, "{} ", dim)
-            })?;
+                    match exp {
+                        -1 => dim.to_string(),
+                        _ => format!("{}^{}", dim, -exp),
+                    }
+                }
+                _ => format!("{}^{}", dim, exp),
+            };
+            write!(out, "{} ", dim)
+        })?;
 
         write!(f, "{}", out.trim_end())
     }
@@ -422,3 +475,48 @@ impl<const N: usize> Div<Dimension> for [Float; N] {
         Vector(self, rhs.inv())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renderings() {
+        let force = Dimension::M * Dimension::L / Dimension::T.pow(2);
+        assert_eq!(force.to_string(), "L M / T^2");
+        assert_eq!(force.to_latex(), "L M T^{-2}");
+        assert_eq!(force.to_unicode(), "L M T⁻²");
+
+        let dim = Dimension::L.pow(2) / Dimension::Θ.pow(12);
+        assert_eq!(dim.to_latex(), "L^{2} \\Theta^{-12}");
+        assert_eq!(dim.to_unicode(), "L² Θ⁻¹²");
+        assert_eq!(Dimension::NONE.to_unicode(), "");
+    }
+
+    #[test]
+    fn test_try_radical() {
+        assert_eq!(Dimension::L.pow(3).try_radical(3), Some(Dimension::L));
+        assert_eq!(
+            Dimension::L.pow(3).try_radical(-3),
+            Some(Dimension::L.inv())
+        );
+        assert_eq!(Dimension::L.pow(2).try_radical(3), None);
+        assert_eq!(Dimension::L.try_radical(0), None);
+        assert_eq!(Dimension::NONE.try_radical(0), None);
+    }
+
+    #[test]
+    fn test_try_pow_rational() {
+        assert_eq!(
+            Dimension::L.pow(2).try_pow_rational(1, 2),
+            Some(Dimension::L)
+        );
+        assert_eq!(Dimension::L.pow(3).try_pow_rational(1, 2), None);
+        assert_eq!(
+            Dimension::L.pow(2).try_pow_rational(3, 2),
+            Some(Dimension::L.pow(3))
+        );
+        assert_eq!(Dimension::L.try_pow_rational(1, 0), None);
+        assert_eq!(Dimension::NONE.try_pow_rational(0, 0), None);
+    }
+}
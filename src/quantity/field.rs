@@ -1,29 +1,49 @@
-use std::{
-    ops::{Add, Mul, Neg},
-    rc::Rc,
-};
+use std::ops::{Add, Mul, Neg, Sub};
+#[cfg(not(feature = "sync"))]
+use std::rc::Rc as Shared;
+#[cfg(feature = "sync")]
+use std::sync::Arc as Shared;
 
 use crate::{
     dimension::{Dimension, DimensionError},
-    units, Scalar, Vector, STEP,
+    units, BoundingBox, Float, Scalar, Vector, STEP,
 };
 
+/// `Send + Sync` with the `sync` feature, which fields then require of their closures
+#[cfg(feature = "sync")]
+pub trait MaybeSync: Send + Sync {}
+#[cfg(feature = "sync")]
+impl<T: Send + Sync + ?Sized> MaybeSync for T {}
+
+/// `Send + Sync` with the `sync` feature, which fields then require of their closures
+#[cfg(not(feature = "sync"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+#[cfg(feature = "sync")]
+type Function<'a, const N: usize, T> = Shared<dyn Fn(Vector<N>) -> T + Send + Sync + 'a>;
+#[cfg(not(feature = "sync"))]
+type Function<'a, const N: usize, T> = Shared<dyn Fn(Vector<N>) -> T + 'a>;
+
+/// With the `sync` feature fields are `Send + Sync`, so one field can be sampled from
+/// several threads
 #[derive(Clone)]
 pub struct ScalarField<'a, const N: usize> {
-    field: Rc<dyn Fn(Vector<N>) -> Scalar + 'a>,
+    field: Function<'a, N, Scalar>,
     dim: Dimension,
 }
 
 impl<const N: usize> ScalarField<'_, N> {
     fn derivative(&self, x: Vector<N>, n: Vector<N>) -> Scalar {
-        (self.at(x + STEP * n).unwrap() - self.at(x - STEP * n).unwrap()) / (2.0 * STEP) / n.dim()
+        let dx = STEP * units::m;
+        (self.at(x + dx * n).unwrap() - self.at(x - dx * n).unwrap()) / (2.0 * dx)
     }
 
     fn derivative2(&self, x: Vector<N>, n: Vector<N>) -> Scalar {
-        (self.at(x + STEP * n).unwrap() - 2.0 * self.at(x).unwrap()
-            + self.at(x - STEP * n).unwrap())
-            / STEP.powi(2)
-            / n.dim().pow(2)
+        let dx = STEP * units::m;
+        (self.at(x + dx * n).unwrap() - 2.0 * self.at(x).unwrap() + self.at(x - dx * n).unwrap())
+            / dx.squared()
     }
 
     pub fn dim(&self) -> Dimension {
@@ -37,7 +57,23 @@ impl<const N: usize> ScalarField<'_, N> {
         Ok(at)
     }
 
-    pub fn gradient(&self) -> VectorField<N> {
+    /// Slope of the field at `x` along `dir`, which only needs two samples where the
+    /// gradient takes `2N`. `dir` is normalized, so only its direction matters
+    pub fn directional_derivative(
+        &self,
+        x: Vector<N>,
+        dir: Vector<N>,
+    ) -> Result<Scalar, DimensionError> {
+        x.dimension_err(units::m.dim(), "x")?;
+        if dir.is_zero() {
+            return Err(DimensionError::new(
+                "Cannot differentiate along a zero vector",
+            ));
+        }
+        Ok(self.derivative(x, dir.normalized()))
+    }
+
+    pub fn gradient(&self) -> VectorField<'_, N> {
         (
             |x| {
                 (0..N).fold(Vector::zero() * self.dim / units::m, |acc, i| {
@@ -49,7 +85,7 @@ impl<const N: usize> ScalarField<'_, N> {
             .into()
     }
 
-    pub fn laplacian(&self) -> ScalarField<N> {
+    pub fn laplacian(&self) -> ScalarField<'_, N> {
         (
             move |x| {
                 (0..N).fold(Scalar::ZERO * self.dim / units::m.powi(2), |acc, i| {
@@ -60,15 +96,80 @@ impl<const N: usize> ScalarField<'_, N> {
         )
             .into()
     }
+
+    /// Midpoint Riemann sum of the field over the box spanned by `min` and `max`
+    pub fn integrate_over(
+        &self,
+        min: Vector<N>,
+        max: Vector<N>,
+        resolution: [usize; N],
+    ) -> Result<Scalar, DimensionError> {
+        min.dimension_err(units::m, "min")?;
+        max.dimension_err(units::m, "max")?;
+
+        let step = (max - min).component_div(resolution.map(|n| n as Float).into());
+        let cells = resolution.iter().product::<usize>();
+
+        let mut sum = Scalar::ZERO * self.dim;
+        for cell in 0..cells {
+            let mut rest = cell;
+            let mut x = min;
+            for (i, &n) in resolution.iter().enumerate() {
+                x[i] += (rest % n) as Float * step[i] + 0.5 * step[i];
+                rest /= n;
+            }
+            sum += self.at(x)?;
+        }
+
+        Ok(sum * step.0.iter().product::<Float>() * units::m.powi(N as i32))
+    }
+}
+
+impl ScalarField<'_, 2> {
+    /// Samples the field at the pixel centers of a `[width, height]` grid over `bounds`
+    /// and colors each pixel with `colormap` applied to the value normalized to `[0, 1]`
+    /// over the sampled range. Rows are RGB from the top (largest y) down, ready for a PNG
+    pub fn to_image_data(
+        &self,
+        bounds: BoundingBox<2>,
+        resolution: [usize; 2],
+        colormap: fn(Float) -> [u8; 3],
+    ) -> Result<Vec<u8>, DimensionError> {
+        let [width, height] = resolution;
+        let step =
+            (bounds.max - bounds.min).component_div([width as Float, height as Float].into());
+        let mut values = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for column in 0..width {
+                let x = bounds.min[0] + (column as Float + 0.5) * step[0];
+                let y = bounds.max[1] - (row as Float + 0.5) * step[1];
+                values.push(self.at(Vector([x, y], bounds.min.dim()))?.value());
+            }
+        }
+
+        let min = values.iter().copied().fold(Float::INFINITY, Float::min);
+        let max = values.iter().copied().fold(Float::NEG_INFINITY, Float::max);
+        let range = max - min;
+        Ok(values
+            .into_iter()
+            .flat_map(|value| {
+                colormap(if range > 0.0 {
+                    (value - min) / range
+                } else {
+                    0.0
+                })
+            })
+            .collect())
+    }
 }
 
 impl<'a, const N: usize, F, D: Into<Dimension>> From<(F, D)> for ScalarField<'a, N>
 where
-    F: Fn(Vector<N>) -> Scalar + 'a,
+    F: Fn(Vector<N>) -> Scalar + MaybeSync + 'a,
 {
     fn from(field: (F, D)) -> Self {
         ScalarField {
-            field: Rc::new(field.0),
+            field: Shared::new(field.0),
             dim: field.1.into(),
         }
     }
@@ -84,7 +185,22 @@ impl<'a, const N: usize> Add for ScalarField<'a, N> {
                 self.dim, rhs.dim
             )
         }
-        self.field = Rc::new(move |x| (self.field)(x) + (rhs.field)(x));
+        self.field = Shared::new(move |x| (self.field)(x) + (rhs.field)(x));
+        self
+    }
+}
+
+impl<'a, const N: usize> Sub for ScalarField<'a, N> {
+    type Output = ScalarField<'a, N>;
+    #[track_caller]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        if self.dim != rhs.dim {
+            panic!(
+                "Cannot subtract scalar fields of dimensions {} and {}",
+                self.dim, rhs.dim
+            )
+        }
+        self.field = Shared::new(move |x| (self.field)(x) - (rhs.field)(x));
         self
     }
 }
@@ -92,7 +208,7 @@ impl<'a, const N: usize> Add for ScalarField<'a, N> {
 impl<'a, const N: usize> Mul<Scalar> for ScalarField<'a, N> {
     type Output = ScalarField<'a, N>;
     fn mul(mut self, rhs: Scalar) -> Self::Output {
-        self.field = Rc::new(move |x| (self.field)(x) * rhs);
+        self.field = Shared::new(move |x| (self.field)(x) * rhs);
         self.dim = self.dim * rhs.1;
         self
     }
@@ -101,22 +217,23 @@ impl<'a, const N: usize> Mul<Scalar> for ScalarField<'a, N> {
 impl<'a, const N: usize> Neg for ScalarField<'a, N> {
     type Output = ScalarField<'a, N>;
     fn neg(mut self) -> Self::Output {
-        self.field = Rc::new(move |x| -(self.field)(x));
+        self.field = Shared::new(move |x| -(self.field)(x));
         self
     }
 }
 
+/// With the `sync` feature fields are `Send + Sync`, so one field can be sampled from
+/// several threads
 #[derive(Clone)]
 pub struct VectorField<'a, const N: usize> {
-    field: Rc<dyn Fn(Vector<N>) -> Vector<N> + 'a>,
+    field: Function<'a, N, Vector<N>>,
     dim: Dimension,
 }
 
 impl<const N: usize> VectorField<'_, N> {
     fn derivative(&self, x: Vector<N>, n: Vector<N>) -> Scalar {
-        (self.at(x + STEP * n).unwrap() - self.at(x - STEP * n).unwrap()).dot(n)
-            / (2.0 * STEP)
-            / n.dim()
+        let dx = STEP * units::m;
+        (self.at(x + dx * n).unwrap() - self.at(x - dx * n).unwrap()).dot(n) / (2.0 * dx)
     }
 
     // fn derivative2(&self, x: Vector<N>, n: Vector<N>) -> Scalar {
@@ -140,7 +257,7 @@ impl<const N: usize> VectorField<'_, N> {
             )
         }
         let old = self.field.clone();
-        self.field = Rc::new(move |x: Vector<N>| {
+        self.field = Shared::new(move |x: Vector<N>| {
             if x.squared() < s.squared() {
                 (new.field)(x)
             } else {
@@ -150,6 +267,67 @@ impl<const N: usize> VectorField<'_, N> {
         Ok(())
     }
 
+    /// Interpolates multilinearly between the samples of a regular grid, clamping to the grid's edges outside of it.
+    /// `data` holds `dims` samples with the first axis varying fastest
+    #[track_caller]
+    pub fn from_grid(
+        origin: Vector<N>,
+        spacing: Vector<N>,
+        data: Vec<Vector<N>>,
+        dims: [usize; N],
+    ) -> VectorField<'static, N> {
+        if let Err(e) = origin
+            .dimension_err(units::m, "origin")
+            .and(spacing.dimension_err(units::m, "spacing"))
+        {
+            panic!("{e}")
+        }
+        if dims.contains(&0) || data.len() != dims.iter().product() {
+            panic!(
+                "Grid of dimensions {dims:?} cannot hold {} samples",
+                data.len()
+            )
+        }
+        let dim = data[0].dim();
+        if let Some(v) = data.iter().find(|v| v.dim() != dim) {
+            panic!(
+                "Grid samples have mismatched dimensions {} and {}",
+                dim,
+                v.dim()
+            )
+        }
+
+        let field = move |x: Vector<N>| {
+            let mut base = [0; N];
+            let mut frac = [0.0; N];
+            for i in 0..N {
+                let t = ((x[i] - origin[i]) / spacing[i]).clamp(0.0, (dims[i] - 1) as Float);
+                base[i] = (t as usize).min(dims[i].saturating_sub(2));
+                frac[i] = t - base[i] as Float;
+            }
+
+            let mut value = [0.0; N];
+            for corner in 0..1usize << N {
+                let mut weight = 1.0;
+                let mut index = 0;
+                let mut stride = 1;
+                for i in 0..N {
+                    let upper = corner >> i & 1;
+                    weight *= if upper == 1 { frac[i] } else { 1.0 - frac[i] };
+                    index += (base[i] + upper).min(dims[i] - 1) * stride;
+                    stride *= dims[i];
+                }
+                if weight != 0.0 {
+                    for (v, sample) in value.iter_mut().zip(data[index].0) {
+                        *v += weight * sample;
+                    }
+                }
+            }
+            Vector(value, dim)
+        };
+        (field, dim).into()
+    }
+
     pub fn at(&self, x: Vector<N>) -> Result<Vector<N>, DimensionError> {
         x.dimension_err(units::m, "x")?;
         let at = (self.field)(x);
@@ -157,7 +335,20 @@ impl<const N: usize> VectorField<'_, N> {
         Ok(at)
     }
 
-    pub fn divergence(&self) -> ScalarField<N> {
+    pub fn magnitude_field(&self) -> ScalarField<'_, N> {
+        (move |x| (self.field)(x).magnitude(), self.dim).into()
+    }
+
+    /// Applies `f` to every vector of the field, `dim` is the dimension of the vectors it returns
+    pub fn map_vectors<'b>(
+        &'b self,
+        f: impl Fn(Vector<N>) -> Vector<N> + MaybeSync + 'b,
+        dim: Dimension,
+    ) -> VectorField<'b, N> {
+        (move |x| f((self.field)(x)), dim).into()
+    }
+
+    pub fn divergence(&self) -> ScalarField<'_, N> {
         (
             move |x| {
                 (0..N).fold(Scalar::ZERO * self.dim / units::m, |acc, i| {
@@ -171,7 +362,7 @@ impl<const N: usize> VectorField<'_, N> {
 }
 
 impl VectorField<'_, 3> {
-    pub fn curl(&self) -> VectorField<3> {
+    pub fn curl(&self) -> VectorField<'_, 3> {
         (
             move |x| {
                 [
@@ -199,11 +390,11 @@ impl VectorField<'_, 3> {
 
 impl<'a, const N: usize, F, D: Into<Dimension>> From<(F, D)> for VectorField<'a, N>
 where
-    F: Fn(Vector<N>) -> Vector<N> + 'a,
+    F: Fn(Vector<N>) -> Vector<N> + MaybeSync + 'a,
 {
     fn from(field: (F, D)) -> Self {
         VectorField {
-            field: Rc::new(field.0),
+            field: Shared::new(field.0),
             dim: field.1.into(),
         }
     }
@@ -219,7 +410,22 @@ impl<'a, const N: usize> Add for VectorField<'a, N> {
                 self.dim, rhs.dim
             )
         }
-        self.field = Rc::new(move |x| (self.field)(x) + (rhs.field)(x));
+        self.field = Shared::new(move |x| (self.field)(x) + (rhs.field)(x));
+        self
+    }
+}
+
+impl<'a, const N: usize> Sub for VectorField<'a, N> {
+    type Output = VectorField<'a, N>;
+    #[track_caller]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        if self.dim != rhs.dim {
+            panic!(
+                "Cannot subtract vector fields of dimensions {} and {}",
+                self.dim, rhs.dim
+            )
+        }
+        self.field = Shared::new(move |x| (self.field)(x) - (rhs.field)(x));
         self
     }
 }
@@ -227,7 +433,7 @@ impl<'a, const N: usize> Add for VectorField<'a, N> {
 impl<'a, const N: usize> Mul<Scalar> for VectorField<'a, N> {
     type Output = VectorField<'a, N>;
     fn mul(mut self, rhs: Scalar) -> Self::Output {
-        self.field = Rc::new(move |x| (self.field)(x) * rhs);
+        self.field = Shared::new(move |x| (self.field)(x) * rhs);
         self.dim = self.dim * rhs.1;
         self
     }
@@ -237,7 +443,7 @@ impl<'a, const N: usize> Mul<Vector<N>> for ScalarField<'a, N> {
     type Output = VectorField<'a, N>;
     fn mul(self, rhs: Vector<N>) -> Self::Output {
         VectorField {
-            field: Rc::new(move |x| (self.field)(x) * rhs),
+            field: Shared::new(move |x| (self.field)(x) * rhs),
             dim: self.dim * rhs.1,
         }
     }
@@ -246,7 +452,7 @@ impl<'a, const N: usize> Mul<Vector<N>> for ScalarField<'a, N> {
 impl<'a, const N: usize> Neg for VectorField<'a, N> {
     type Output = VectorField<'a, N>;
     fn neg(mut self) -> Self::Output {
-        self.field = Rc::new(move |x| -(self.field)(x));
+        self.field = Shared::new(move |x| -(self.field)(x));
         self
     }
 }
@@ -256,6 +462,67 @@ mod tests {
     use super::*;
     use crate::units::*;
 
+    #[test]
+    fn test_to_image_data() {
+        let f = ScalarField::from((|x: Vector<2>| x[0] * V, V));
+        let gray = |level: Float| [(level * 255.0).round() as u8; 3];
+        let bounds = BoundingBox {
+            min: [0.0, 0.0] * m,
+            max: [4.0, 2.0] * m,
+        };
+        let image = f.to_image_data(bounds, [4, 3], gray).unwrap();
+        assert_eq!(image.len(), 4 * 3 * 3);
+        // Leftmost and rightmost columns hold the smallest and largest values
+        for row in image.chunks(4 * 3) {
+            assert_eq!(row[..3], [0; 3]);
+            assert_eq!(row[9..], [255; 3]);
+        }
+        assert!(f
+            .to_image_data(
+                BoundingBox {
+                    min: [0.0, 0.0] * s,
+                    max: [1.0, 1.0] * s
+                },
+                [2, 2],
+                gray
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_sample_across_threads() {
+        let f = ScalarField::from((|x: Vector<2>| x.squared() / m, m));
+        let grad = f.gradient();
+        let points = (0..64)
+            .map(|i| [i as Float * 0.25, 1.0 - i as Float * 0.5] * m)
+            .collect::<Vec<_>>();
+        let serial = points
+            .iter()
+            .map(|&x| grad.at(x).unwrap())
+            .collect::<Vec<_>>();
+
+        let parallel = std::thread::scope(|scope| {
+            let handles = points
+                .chunks(16)
+                .map(|chunk| {
+                    let grad = &grad;
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|&x| grad.at(x).unwrap())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(parallel, serial);
+    }
+
     #[test]
     fn test_grad() {
         let f = ScalarField::from((|x: Vector<3>| x[0] * m, m));
@@ -263,6 +530,22 @@ mod tests {
         assert_eq!(f.at(Vector::zero() * m).unwrap().0[0], 1.0)
     }
 
+    #[test]
+    fn test_directional_derivative() {
+        let f = ScalarField::from((|x: Vector<2>| x.dot([3.0, 4.0] * V / m), V));
+        let x = [1.0, 2.0] * m;
+        let along = f.directional_derivative(x, [6.0, 8.0] * m).unwrap();
+        assert_eq!(along.dim(), (V / m).dim());
+        assert!((along - 5.0 * V / m).abs() < 1e-2 * V / m, "{along:?}");
+        let across = f.directional_derivative(x, [-4.0, 3.0].into()).unwrap();
+        assert!(across.abs() < 1e-2 * V / m, "{across:?}");
+
+        assert!(f.directional_derivative(x, Vector::zero()).is_err());
+        assert!(f
+            .directional_derivative([1.0, 2.0] * s, Vector::<2>::i)
+            .is_err());
+    }
+
     #[test]
     fn test_div() {
         let f = VectorField::from((|x: Vector<3>| x, m));
@@ -285,4 +568,71 @@ mod tests {
         assert_eq!(curl.at(Vector::zero() * m).unwrap(), Vector::zero());
         assert_eq!(curl.at([2.0, 3.5, 7.8] * m).unwrap(), Vector::zero());
     }
+
+    #[test]
+    fn test_sub() {
+        let f = ScalarField::from((|x: Vector<3>| x.dot(x) / m, m));
+        let difference = f.clone() - f;
+        let g = VectorField::from((|x: Vector<3>| x * x[1], m));
+        let vector_difference = g.clone() - g;
+        for x in [[0.0, 0.0, 0.0], [1.5, -2.0, 3.0], [10.0, 4.0, -7.5]] {
+            assert!(difference.at(x * m).unwrap().abs() < 1e-6 * m);
+            assert!(vector_difference.at(x * m).unwrap().is_zero());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot subtract vector fields")]
+    fn test_sub_dimension_mismatch() {
+        let f = VectorField::from((|x: Vector<3>| x, m));
+        let g = VectorField::from((|x: Vector<3>| x / s, m / s));
+        let _ = f - g;
+    }
+
+    #[test]
+    fn test_magnitude_field() {
+        let f = VectorField::from((|x: Vector<3>| x, m));
+        let magnitude = f.magnitude_field();
+        assert_eq!(magnitude.dim(), m.dim());
+        assert_eq!(magnitude.at([3.0, 4.0, 0.0] * m).unwrap(), 5.0 * m);
+
+        let velocity = f.map_vectors(|x| x * 2.0 / s, (m / s).dim());
+        assert_eq!(
+            velocity.at([1.0, 2.0, 3.0] * m).unwrap(),
+            [2.0, 4.0, 6.0] * (m / s)
+        );
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let data = [[0.0, 0.0], [2.0, 0.0], [0.0, 4.0], [2.0, 8.0]]
+            .map(|v| Vector(v, T.dim()))
+            .to_vec();
+        let f = VectorField::from_grid(Vector::zero() * m, [1.0, 2.0] * m, data, [2, 2]);
+        assert_eq!(f.dim(), T.dim());
+        assert_eq!(f.at([1.0, 2.0] * m).unwrap(), [2.0, 8.0] * T);
+        assert_eq!(f.at([0.5, 1.0] * m).unwrap(), [1.0, 3.0] * T);
+        assert_eq!(f.at([5.0, -1.0] * m).unwrap(), [2.0, 0.0] * T);
+    }
+
+    #[test]
+    fn test_integrate_over() {
+        let density = 2.0 * kg / m.powi(3);
+        let f = ScalarField::from((move |_: Vector<3>| density, density));
+        let mass = f
+            .integrate_over(Vector::zero() * m, [2.0, 3.0, 1.0] * m, [4, 5, 6])
+            .unwrap();
+        assert_eq!(mass.dim(), kg.dim());
+        assert!((mass - 12.0 * kg).abs() < 1e-4 * kg);
+
+        let f = ScalarField::from((|x: Vector<3>| x[0] * kg / m.powi(3), kg / m.powi(3)));
+        let mass = f
+            .integrate_over(Vector::zero() * m, [2.0, 1.0, 1.0] * m, [8, 2, 2])
+            .unwrap();
+        assert!((mass - 2.0 * kg).abs() < 1e-4 * kg);
+
+        assert!(f
+            .integrate_over(Vector::zero() * s, [1.0, 1.0, 1.0] * s, [1, 1, 1])
+            .is_err());
+    }
 }
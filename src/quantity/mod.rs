@@ -1,21 +1,25 @@
 pub mod consts;
 pub mod dimension;
 pub mod field;
+pub mod quaternion;
+pub mod registry;
 pub mod scalar;
+pub mod sum;
 // pub mod tensor;
 pub mod vector;
 
 pub use consts::*;
-pub use field::{ScalarField, VectorField};
-pub use scalar::Scalar;
+pub use field::{MaybeSync, ScalarField, VectorField};
+pub use quaternion::Quaternion;
+pub use registry::UnitRegistry;
+pub use scalar::{RangeDiagnostic, RangeError, Scalar};
+pub use sum::CompensatedSum;
 // pub use tensor::Tensor;
 pub use vector::Vector;
 
 pub type Float = f32;
-pub use std::f32::{
-    consts::{E, PI},
-    EPSILON,
-};
+pub use std::f32::consts::{E, PI};
+pub const EPSILON: Float = Float::EPSILON;
 
 use super::STEP;
 
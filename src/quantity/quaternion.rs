@@ -0,0 +1,182 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Mul},
+};
+
+use crate::{dimension::Dimension, Float, Scalar, Vector, PI};
+
+/// Dimensionless quaternion `w + xi + yj + zk`, unit quaternions represent 3D rotations
+#[derive(Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: Float,
+    pub x: Float,
+    pub y: Float,
+    pub z: Float,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(w: Float, x: Float, y: Float, z: Float) -> Quaternion {
+        Quaternion { w, x, y, z }
+    }
+
+    /// Rotation by `angle` radians around `axis`, following the right hand rule
+    pub fn from_axis_angle(axis: Vector<3>, angle: Float) -> Quaternion {
+        let axis = axis.normalized();
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Quaternion::new(cos, axis[0] * sin, axis[1] * sin, axis[2] * sin)
+    }
+
+    /// Shortest arc rotation turning the direction of `from` onto the direction of `to`.
+    /// Opposite vectors give a half turn around some axis perpendicular to both
+    pub fn from_to(from: Vector<3>, to: Vector<3>) -> Quaternion {
+        let (from, to) = (from.normalized(), to.normalized());
+        let cos = from.dot(to).value();
+        if cos < -1.0 + 1e-6 {
+            let mut axis = from.cross(Vector::<3>::i);
+            if axis.magnitude() < 1e-3 {
+                axis = from.cross(Vector::<3>::j);
+            }
+            return Quaternion::from_axis_angle(axis, PI);
+        }
+        let axis = from.cross(to);
+        Quaternion::new(1.0 + cos, axis[0], axis[1], axis[2]).normalized()
+    }
+
+    /// Pure quaternion `xi + yj + zk` holding the components of `v`
+    pub fn from_vector(v: Vector<3>) -> Quaternion {
+        Quaternion::new(0.0, v[0], v[1], v[2])
+    }
+
+    /// The imaginary part as a vector of dimension `dim`
+    pub fn vector(&self, dim: impl Into<Dimension>) -> Vector<3> {
+        Vector([self.x, self.y, self.z], dim.into())
+    }
+
+    pub fn conjugate(self) -> Quaternion {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn norm(&self) -> Float {
+        (self.w.powi(2) + self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
+    }
+
+    pub fn normalized(self) -> Quaternion {
+        self * self.norm().recip()
+    }
+
+    /// Angle of the rotation in radians, between 0 and 2π
+    pub fn angle(&self) -> Float {
+        2.0 * self.w.clamp(-1.0, 1.0).acos()
+    }
+
+    /// Rotates `v` by this unit quaternion, keeping its dimension. Computed as `q v q⁻¹`
+    /// so the rounding of the norm doesn't scale `v`
+    pub fn rotate(&self, v: Vector<3>) -> Vector<3> {
+        let norm_squared = self.w.powi(2) + self.x.powi(2) + self.y.powi(2) + self.z.powi(2);
+        (*self * Quaternion::from_vector(v) * self.conjugate()).vector(v.dim()) / norm_squared
+    }
+
+    /// Advances the orientation by the angular velocity `ω` over `dt`
+    /// with `q' = q + ½ ω q dt`, renormalized
+    pub fn integrate(self, ω: Vector<3>, dt: Scalar) -> Quaternion {
+        let dq = Quaternion::from_vector(ω) * self * (0.5 * (dt * ω.dim()).value());
+        (self + dq).normalized()
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Quaternion;
+    fn add(self, rhs: Quaternion) -> Self::Output {
+        Quaternion::new(
+            self.w + rhs.w,
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+        )
+    }
+}
+
+/// Hamilton product
+impl Mul for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: Quaternion) -> Self::Output {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+impl Mul<Float> for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: Float) -> Self::Output {
+        Quaternion::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Debug for Quaternion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2?} + {:.2?}i + {:.2?}j + {:.2?}k",
+            self.w, self.x, self.y, self.z
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::*;
+
+    #[test]
+    fn test_rotate() {
+        let q = Quaternion::from_axis_angle(Vector::<3>::k, PI / 2.0);
+        let v = q.rotate([2.0, 0.0, 0.0] * m);
+        assert_eq!(v.dim(), m.dim());
+        assert!((v - [0.0, 2.0, 0.0] * m).magnitude() < 1e-6 * m);
+        assert!((q.angle() - PI / 2.0).abs() < 1e-6);
+
+        let identity = q * q.conjugate();
+        assert!((identity.w - 1.0).abs() < 1e-6 && identity.vector(Dimension::NONE).is_zero());
+    }
+
+    #[test]
+    fn test_from_to() {
+        let q = Quaternion::from_to(Vector::<3>::i, 3.0 * Vector::<3>::j);
+        assert!((q.rotate(Vector::<3>::i) - Vector::<3>::j).magnitude() < 1e-6);
+        assert!((q.angle() - PI / 2.0).abs() < 1e-6);
+
+        let same = Quaternion::from_to([1.0, 2.0, 3.0] * m, [2.0, 4.0, 6.0] * m);
+        assert!((same.w - 1.0).abs() < 1e-6 && same.vector(Dimension::NONE).is_zero());
+
+        for from in [Vector::<3>::i, [1.0, -2.0, 0.5].into()] {
+            let q = Quaternion::from_to(from, -from);
+            assert!((q.angle() - PI).abs() < 1e-5);
+            assert!(q.vector(Dimension::NONE).dot(from).abs() < 1e-6);
+            assert!((q.rotate(from) + from).magnitude() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_integrate() {
+        let ω = [0.0, 0.0, 2.0] / s;
+        let mut q = Quaternion::IDENTITY;
+        for _ in 0..1000 {
+            q = q.integrate(ω, 1e-3 * s);
+        }
+        assert!((q.norm() - 1.0).abs() < 1e-6);
+        assert!((q.angle() - 2.0).abs() < 1e-3);
+        assert!((q.vector(Dimension::NONE).normalized() - Vector::<3>::k).is_zero());
+    }
+}
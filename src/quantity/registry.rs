@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use crate::{
+    dimension::{Dimension, DimensionError, SIPrefix},
+    units::*,
+    Float, Scalar,
+};
+
+const PREFIXES: [(&str, SIPrefix); 24] = [
+    ("Q", SIPrefix::Q),
+    ("R", SIPrefix::R),
+    ("Y", SIPrefix::Y),
+    ("Z", SIPrefix::Z),
+    ("E", SIPrefix::E),
+    ("P", SIPrefix::P),
+    ("T", SIPrefix::N),
+    ("G", SIPrefix::G),
+    ("M", SIPrefix::M),
+    ("k", SIPrefix::k),
+    ("h", SIPrefix::h),
+    ("da", SIPrefix::da),
+    ("d", SIPrefix::d),
+    ("c", SIPrefix::c),
+    ("m", SIPrefix::m),
+    ("μ", SIPrefix::μ),
+    ("n", SIPrefix::n),
+    ("p", SIPrefix::p),
+    ("f", SIPrefix::f),
+    ("a", SIPrefix::a),
+    ("z", SIPrefix::z),
+    ("y", SIPrefix::y),
+    ("r", SIPrefix::r),
+    ("q", SIPrefix::q),
+];
+
+/// Maps unit symbols to their definitions, for reading quantities like `"9.81 m/s^2"`
+/// and writing them back in a fitting unit.
+///
+/// Every registered symbol can be parsed, with or without an SI prefix. Only display units
+/// are considered by [`UnitRegistry::format`], aliases are for parsing alone
+#[derive(Clone, Debug, Default)]
+pub struct UnitRegistry {
+    symbols: HashMap<String, Scalar>,
+    display: HashMap<Dimension, Vec<(String, Scalar)>>,
+}
+
+impl UnitRegistry {
+    /// An empty registry
+    pub fn new() -> UnitRegistry {
+        UnitRegistry::default()
+    }
+
+    /// The units of [`units`](crate::units): SI units are display units,
+    /// the other named and imperial units are aliases
+    pub fn standard() -> UnitRegistry {
+        let mut registry = UnitRegistry::new();
+        for (symbol, unit) in [
+            ("kg", kg),
+            ("m", m),
+            ("s", s),
+            ("A", A),
+            ("K", K),
+            ("cd", cd),
+            ("mol", mol),
+            ("Hz", Hz),
+            ("N", N),
+            ("Pa", Pa),
+            ("J", J),
+            ("W", W),
+            ("C", C),
+            ("V", V),
+            ("F", F),
+            ("Ω", Ω),
+            ("S", S),
+            ("Wb", Wb),
+            ("T", T),
+            ("H", H),
+            ("lx", lx),
+            ("Gy", Gy),
+            ("kat", kat),
+        ] {
+            registry.register(symbol, unit);
+        }
+        for (symbol, unit) in [
+            ("g", 1e-3 * kg),
+            ("rad", rad),
+            ("sr", sr),
+            ("Ohm", Ohm),
+            ("lm", lm),
+            ("Bq", Bq),
+            ("Sv", Sv),
+            ("VA", VA),
+            ("min", min),
+            ("hr", hr),
+            ("d", d),
+            ("au", au),
+            ("deg", deg),
+            ("°", deg),
+            ("arcmin", arcmin),
+            ("arcsec", arcsec),
+            ("ha", ha),
+            ("L", L),
+            ("t", t),
+            ("Da", Da),
+            ("u", u),
+            ("Gal", Gal),
+            ("pc", pc),
+            ("bar", bar),
+            ("atm", atm),
+            ("Å", Å),
+            ("twip", twip),
+            ("th", th),
+            ("barleycorn", barleycorn),
+            ("in", inch),
+            ("hh", hh),
+            ("ft", ft),
+            ("yd", yd),
+            ("ch", ch),
+            ("fur", fur),
+            ("mi", mi),
+            ("lea", lea),
+            ("ftm", ftm),
+            ("nmi", nmi),
+            ("link", link),
+            ("rod", rod),
+            ("perch", perch),
+            ("rood", rood),
+            ("acre", acre),
+            ("sq_mi", sq_mi),
+            ("fl_oz", fl_oz),
+            ("gi", gi),
+            ("pt", pt),
+            ("qt", qt),
+            ("gal", gal),
+            ("gr", gr),
+            ("dr", dr),
+            ("oz", oz),
+            ("lb", lb),
+            ("st", st),
+            ("qtr", qtr),
+            ("cwt", cwt),
+            ("ton", ton),
+            ("slug", slug),
+        ] {
+            registry.register_alias(symbol, unit);
+        }
+        registry
+    }
+
+    /// Registers a unit that can be parsed and that `format` may pick,
+    /// replacing any unit with the same symbol
+    pub fn register(&mut self, symbol: &str, unit: Scalar) {
+        self.register_alias(symbol, unit);
+        self.display
+            .entry(unit.dim())
+            .or_default()
+            .push((symbol.to_string(), unit));
+    }
+
+    /// Registers a unit that can be parsed but is never picked by `format`
+    pub fn register_alias(&mut self, symbol: &str, unit: Scalar) {
+        if let Some(old) = self.symbols.insert(symbol.to_string(), unit) {
+            if let Some(units) = self.display.get_mut(&old.dim()) {
+                units.retain(|(registered, _)| registered != symbol);
+            }
+        }
+    }
+
+    /// Definition of `symbol`, which may carry an SI prefix like `km` or `μs`
+    pub fn unit(&self, symbol: &str) -> Option<Scalar> {
+        if let Some(&unit) = self.symbols.get(symbol) {
+            return Some(unit);
+        }
+        PREFIXES.iter().find_map(|&(prefix, factor)| {
+            let unit = self.symbols.get(symbol.strip_prefix(prefix)?)?;
+            Some(*unit * factor)
+        })
+    }
+
+    /// Parses a number followed by a unit expression, like `"3 ft/s"` or `"2.5 kg m / s^2"`.
+    ///
+    /// Units are separated by spaces, `*` or `·` and may carry an integer exponent
+    /// after `^`. Every unit after a `/` divides
+    pub fn parse(&self, input: &str) -> Result<Scalar, DimensionError> {
+        let input = input.trim();
+        let split = input.find(char::is_whitespace).unwrap_or(input.len());
+        let (number, units) = input.split_at(split);
+        let value = number
+            .parse::<Float>()
+            .map_err(|_| DimensionError(format!("Invalid number `{number}` in `{input}`")))?;
+
+        let mut result = Scalar::new_finite(value, Dimension::NONE)?;
+        for (i, part) in units.split('/').enumerate() {
+            if i > 1 {
+                return Err(DimensionError(format!("More than one `/` in `{input}`")));
+            }
+            for factor in part
+                .split(|c: char| c.is_whitespace() || c == '*' || c == '·')
+                .filter(|factor| !factor.is_empty())
+            {
+                let (symbol, exponent) = match factor.split_once('^') {
+                    Some((symbol, exponent)) => (
+                        symbol,
+                        exponent.parse::<i32>().map_err(|_| {
+                            DimensionError(format!("Invalid exponent in `{factor}`"))
+                        })?,
+                    ),
+                    None => (factor, 1),
+                };
+                let unit = self
+                    .unit(symbol)
+                    .ok_or_else(|| DimensionError(format!("Unknown unit `{symbol}`")))?;
+                let unit = unit.powi(if i == 0 { exponent } else { -exponent });
+                result = result * unit;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Writes `quantity` in the registered display unit of the same dimension giving the
+    /// smallest value of at least 1, or the largest value if every unit gives less.
+    /// Falls back to SI without a display unit for the dimension
+    pub fn format(&self, quantity: Scalar) -> String {
+        let Some(units) = self.display.get(&quantity.dim()) else {
+            return quantity.to_string();
+        };
+        let values = units
+            .iter()
+            .map(|(symbol, unit)| (symbol, quantity.value() / unit.value()));
+        let best = values
+            .clone()
+            .filter(|(_, x)| x.abs() >= 1.0)
+            .min_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+            .or_else(|| values.max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs())));
+        match best {
+            Some((symbol, x)) => format!("{x} {symbol}"),
+            None => quantity.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let registry = UnitRegistry::standard();
+        let g = registry.parse("9.81 m/s^2").unwrap();
+        assert_eq!(g.dim(), (m / s.squared()).dim());
+        assert!((g.value() - 9.81).abs() < 1e-6);
+
+        let speed = registry.parse("5 mi/hr").unwrap();
+        assert_eq!(speed.dim(), (m / s).dim());
+        assert!((speed.value() - 5.0 * 1609.344 / 3600.0).abs() < 1e-5);
+
+        let force = registry.parse("2 kg m / s^2").unwrap();
+        assert_eq!(force, 2.0 * N);
+        let length = registry.parse("3 km").unwrap();
+        assert_eq!(length, 3000.0 * m);
+        assert_eq!(registry.parse("0.5").unwrap(), Scalar::from(0.5));
+
+        assert!(registry.parse("3 furlongs").is_err());
+        assert!(registry.parse("three m").is_err());
+        assert!(registry.parse("1 m/s/s").is_err());
+        assert!(registry.parse("NaN m").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let mut registry = UnitRegistry::standard();
+        assert_eq!(registry.format(2.0 * N), "2 N");
+        assert_eq!(registry.format(3.0 * m / s), (3.0 * m / s).to_string());
+
+        registry.register("m/s", m / s);
+        registry.register("km/s", 1000.0 * m / s);
+        assert_eq!(registry.format(500.0 * m / s), "500 m/s");
+        assert_eq!(registry.format(2000.0 * m / s), "2 km/s");
+        assert_eq!(registry.format(0.5 * m / s), "0.5 m/s");
+
+        let speed = registry.parse("5 ft/s").unwrap();
+        assert_eq!(registry.parse(&registry.format(speed)).unwrap(), speed);
+    }
+}
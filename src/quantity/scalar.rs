@@ -1,6 +1,8 @@
 use std::{
-    fmt::Debug,
+    cmp::Ordering,
+    fmt::{Debug, Display},
     ops::{Add, AddAssign, Deref, DerefMut, Div, Mul, Neg, Sub, SubAssign},
+    panic::Location,
 };
 
 use crate::{
@@ -8,12 +10,112 @@ use crate::{
     Float,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy)]
 pub struct Scalar(pub Float, pub Dimension);
 
+/// How a product or quotient of finite values left the range of `Float`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeError {
+    /// The result is infinite or NaN
+    NonFinite,
+    /// Neither operand is zero but the result is
+    Underflow,
+}
+
+/// A multiplication or division of `Scalar`s whose result fell out of the range of `Float`,
+/// see [`Scalar::take_range_diagnostics`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeDiagnostic {
+    pub error: RangeError,
+    /// `'*'` or `'/'`
+    pub operation: char,
+    pub lhs: Float,
+    pub rhs: Float,
+    pub location: &'static Location<'static>,
+}
+
+/// Diagnostics kept per thread, later ones are dropped
+#[cfg(debug_assertions)]
+const MAX_RANGE_DIAGNOSTICS: usize = 64;
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static RANGE_DIAGNOSTICS: std::cell::RefCell<Vec<RangeDiagnostic>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Records `lhs operation rhs = result` if the operands are finite but the result isn't,
+/// or neither is zero but the result is. Compiled out of release builds
+#[inline(always)]
+#[track_caller]
+fn check_range(operation: char, lhs: Float, rhs: Float, result: Float) {
+    #[cfg(debug_assertions)]
+    {
+        if !lhs.is_finite() || !rhs.is_finite() {
+            return;
+        }
+        let error = if !result.is_finite() {
+            RangeError::NonFinite
+        } else if result == 0.0 && lhs != 0.0 && rhs != 0.0 {
+            RangeError::Underflow
+        } else {
+            return;
+        };
+        let location = Location::caller();
+        RANGE_DIAGNOSTICS.with_borrow_mut(|diagnostics| {
+            if diagnostics.len() < MAX_RANGE_DIAGNOSTICS {
+                diagnostics.push(RangeDiagnostic {
+                    error,
+                    operation,
+                    lhs,
+                    rhs,
+                    location,
+                });
+            }
+        });
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = (operation, lhs, rhs, result);
+}
+
 impl Scalar {
     pub const ZERO: Scalar = Scalar(0.0, Dimension::NONE);
 
+    /// Usable in `const` and `static` items, e.g. for tables of constants
+    pub const fn new(value: Float, dim: Dimension) -> Scalar {
+        Scalar(value, dim)
+    }
+
+    /// Takes the products and quotients of finite scalars that overflowed to infinity or NaN
+    /// or underflowed to zero on this thread since the last call, at most 64 of them.
+    /// Only debug builds check, release builds always return nothing
+    pub fn take_range_diagnostics() -> Vec<RangeDiagnostic> {
+        #[cfg(debug_assertions)]
+        return RANGE_DIAGNOSTICS.take();
+        #[cfg(not(debug_assertions))]
+        Vec::new()
+    }
+
+    /// Rejects NaN and infinite values, for input read from files or the network
+    pub fn new_finite(value: Float, dim: Dimension) -> Result<Scalar, DimensionError> {
+        if !value.is_finite() {
+            return Err(DimensionError(format!(
+                "Expected a finite value, found {value}"
+            )));
+        }
+        Ok(Scalar(value, dim))
+    }
+
+    /// Dimensionless one
+    pub const fn one() -> Scalar {
+        Scalar(1.0, Dimension::NONE)
+    }
+
+    /// One of the given dimension
+    pub const fn unit(dim: Dimension) -> Scalar {
+        Scalar(1.0, dim)
+    }
+
     pub const fn value(&self) -> Float {
         self.0
     }
@@ -23,6 +125,21 @@ impl Scalar {
         self.0.abs() <= Float::EPSILON
     }
 
+    /// Whether the value is above zero, same as `self > Scalar::ZERO`
+    pub fn is_positive(&self) -> bool {
+        self.0 > 0.0
+    }
+
+    /// Whether the value is below zero, same as `self < Scalar::ZERO`
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0.0
+    }
+
+    /// Sign of the value as 1, -1 or NaN, see [`Float::signum`]
+    pub fn signum(&self) -> Float {
+        self.0.signum()
+    }
+
     pub fn checked_add(self, other: Scalar) -> Option<Scalar> {
         if self.1 != other.1 {
             None
@@ -39,6 +156,44 @@ impl Scalar {
         }
     }
 
+    /// Zero is the same quantity in every dimension, so scalars of different dimensions
+    /// can still be compared when either of them is zero
+    fn comparable(&self, other: Scalar) -> bool {
+        self.1 == other.1 || self.0 == 0.0 || other.0 == 0.0
+    }
+
+    /// Orders the scalars without panicking, NaN values cannot be ordered.
+    /// Like comparisons, allows any dimension when either value is zero
+    pub fn checked_cmp(&self, other: Scalar) -> Result<Ordering, DimensionError> {
+        if !self.comparable(other) {
+            return Err(DimensionError::new(&format!(
+                "Cannot compare scalars with different dimensions: {} and {}",
+                self.1, other.1
+            )));
+        }
+        self.0
+            .partial_cmp(&other.0)
+            .ok_or_else(|| DimensionError::new(&format!("Cannot order {:?} and {:?}", self, other)))
+    }
+
+    /// The value expressed in `unit`, e.g. `(2.0 * km).value_in(m)` is 2000
+    pub fn value_in(&self, unit: Scalar) -> Result<Float, DimensionError> {
+        self.dimension_err(unit, "value")?;
+        Ok(self.0 / unit.0)
+    }
+
+    /// Total order of [`Float::total_cmp`], so NaN values sort after every number
+    /// (or before them when negative). Only the dimensions can make it fail
+    pub fn total_cmp(&self, other: Scalar) -> Result<Ordering, DimensionError> {
+        if self.1 != other.1 {
+            return Err(DimensionError::new(&format!(
+                "Cannot compare scalars with different dimensions: {} and {}",
+                self.1, other.1
+            )));
+        }
+        Ok(self.0.total_cmp(&other.0))
+    }
+
     pub fn dimension_err(
         &self,
         dim: impl Into<Dimension>,
@@ -73,6 +228,12 @@ impl Scalar {
     }
 
     #[inline(always)]
+    pub fn cbrt(self) -> Scalar {
+        Scalar(self.0.cbrt(), self.1.radical(3))
+    }
+
+    #[inline(always)]
+    #[track_caller]
     pub fn radical(self, n: i32) -> Scalar {
         Scalar(self.0.powf(1.0 / n as Float), self.1.radical(n))
     }
@@ -91,6 +252,45 @@ impl Scalar {
     pub fn recip(self) -> Scalar {
         Scalar(self.0.recip(), self.1.inv())
     }
+
+    #[track_caller]
+    pub fn lerp(self, other: Scalar, t: Float) -> Scalar {
+        self * (1.0 - t) + other * t
+    }
+
+    /// Where `self` lies between `a` and `b`, the inverse of [`Scalar::lerp`]
+    #[track_caller]
+    pub fn inverse_lerp(self, a: Scalar, b: Scalar) -> Float {
+        ((self - a) / (b - a)).value()
+    }
+
+    /// The value rounded to `sig_figs` significant figures followed by the dimension
+    pub fn format(&self, sig_figs: usize) -> String {
+        with_dimension(format_sig_figs(self.0, sig_figs), self.1)
+    }
+}
+
+/// Uses scientific notation for very large or small magnitudes
+pub(crate) fn format_sig_figs(x: Float, sig_figs: usize) -> String {
+    let sig_figs = sig_figs.max(1);
+    let scientific = format!("{:.*e}", sig_figs - 1, x);
+    let exp = scientific
+        .rsplit_once('e')
+        .and_then(|(_, exp)| exp.parse::<i32>().ok())
+        .unwrap_or(0);
+    if !x.is_finite() || exp < -4 || exp >= sig_figs as i32 {
+        scientific
+    } else {
+        format!("{:.*}", (sig_figs as i32 - 1 - exp).max(0) as usize, x)
+    }
+}
+
+pub(crate) fn with_dimension(value: String, dim: Dimension) -> String {
+    if dim == Dimension::NONE {
+        value
+    } else {
+        format!("{value} {dim}")
+    }
 }
 
 impl Default for Scalar {
@@ -105,6 +305,16 @@ impl Debug for Scalar {
     }
 }
 
+impl Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)?;
+        if self.1 != Dimension::NONE {
+            write!(f, " {}", self.1)?;
+        }
+        Ok(())
+    }
+}
+
 impl From<Float> for Scalar {
     fn from(a: Float) -> Self {
         a * Dimension::NONE
@@ -196,13 +406,17 @@ impl Sub<Scalar> for Float {
 
 impl Mul<Float> for Scalar {
     type Output = Scalar;
+    #[track_caller]
     fn mul(self, other: Float) -> Scalar {
-        Scalar(self.0 * other, self.1)
+        let value = self.0 * other;
+        check_range('*', self.0, other, value);
+        Scalar(value, self.1)
     }
 }
 
 impl Mul<Scalar> for Float {
     type Output = Scalar;
+    #[track_caller]
     fn mul(self, other: Scalar) -> Scalar {
         other * self
     }
@@ -210,29 +424,41 @@ impl Mul<Scalar> for Float {
 
 impl Div<Float> for Scalar {
     type Output = Scalar;
+    #[track_caller]
     fn div(self, other: Float) -> Scalar {
-        Scalar(self.0 / other, self.1)
+        let value = self.0 / other;
+        check_range('/', self.0, other, value);
+        Scalar(value, self.1)
     }
 }
 
 impl Div<Scalar> for Float {
     type Output = Scalar;
+    #[track_caller]
     fn div(self, other: Scalar) -> Scalar {
-        Scalar(self / other.0, other.1.inv())
+        let value = self / other.0;
+        check_range('/', self, other.0, value);
+        Scalar(value, other.1.inv())
     }
 }
 
 impl Mul for Scalar {
     type Output = Scalar;
+    #[track_caller]
     fn mul(self, other: Scalar) -> Scalar {
-        Scalar(self.0 * other.0, self.1 * other.1)
+        let value = self.0 * other.0;
+        check_range('*', self.0, other.0, value);
+        Scalar(value, self.1 * other.1)
     }
 }
 
 impl Div for Scalar {
     type Output = Scalar;
+    #[track_caller]
     fn div(self, other: Scalar) -> Scalar {
-        Scalar(self.0 / other.0, self.1 / other.1)
+        let value = self.0 / other.0;
+        check_range('/', self.0, other.0, value);
+        Scalar(value, self.1 / other.1)
     }
 }
 
@@ -271,10 +497,27 @@ impl Mul<Scalar> for SIPrefix {
     }
 }
 
+impl Div<SIPrefix> for Scalar {
+    type Output = Scalar;
+    fn div(self, rhs: SIPrefix) -> Self::Output {
+        self / Float::powi(10.0, rhs as _)
+    }
+}
+
+/// Scalars are equal when both their values and dimensions are, except that zero equals zero
+/// in every dimension
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && (self.1 == other.1 || self.0 == 0.0)
+    }
+}
+
+/// Panics when the dimensions differ, unless either value is zero: `x > Scalar::ZERO`
+/// checks the sign of `x` whatever its dimension, consistently with equality
 impl PartialOrd for Scalar {
     #[track_caller]
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.1 != other.1 {
+        if !self.comparable(*other) {
             panic!(
                 "Cannot compare scalars with different dimensions: {} and {}",
                 self.1, other.1
@@ -331,7 +574,7 @@ impl Mul<Scalar> for Dimension {
 impl Div<Scalar> for Dimension {
     type Output = Scalar;
     fn div(self, rhs: Scalar) -> Self::Output {
-        Scalar(rhs.0, rhs.1 / self)
+        Scalar(rhs.0.recip(), self / rhs.1)
     }
 }
 
@@ -346,3 +589,150 @@ impl From<Scalar> for Float {
         val.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::*;
+
+    #[test]
+    fn test_dimension_div_scalar() {
+        assert_eq!(m.dim() / (2.0 * s), 0.5 * m / s);
+        assert_eq!(m.dim() / (4.0 * m), 0.25 * m / m);
+        assert_eq!(m.dim() / (2.0 * s), m.dim() * (2.0 * s).recip());
+    }
+
+    #[test]
+    fn test_constructors() {
+        assert_eq!(Scalar::one(), Scalar(1.0, Dimension::NONE));
+        let unit = Scalar::unit(N.dim());
+        assert_eq!(unit.value(), 1.0);
+        assert_eq!(unit.dim(), N.dim());
+    }
+
+    #[test]
+    fn test_const_new() {
+        const SPEED_OF_SOUND: Scalar = Scalar::new(343.0, m.dim().div(s.dim()));
+        static MACH_2: Scalar = Scalar::new(2.0 * SPEED_OF_SOUND.value(), SPEED_OF_SOUND.dim());
+        assert_eq!(SPEED_OF_SOUND, 343.0 * m / s);
+        assert_eq!(MACH_2, 686.0 * m / s);
+    }
+
+    #[test]
+    fn test_new_finite() {
+        assert_eq!(Scalar::new_finite(2.0, m.dim()).unwrap(), 2.0 * m);
+        assert!(Scalar::new_finite(Float::NAN, m.dim()).is_err());
+        assert!(Scalar::new_finite(Float::NEG_INFINITY, m.dim()).is_err());
+    }
+
+    #[test]
+    fn test_checked_cmp() {
+        assert_eq!((1.0 * m).checked_cmp(2.0 * m).unwrap(), Ordering::Less);
+        assert_eq!((2.0 * s).checked_cmp(2.0 * s).unwrap(), Ordering::Equal);
+        assert!(m.checked_cmp(s).is_err());
+        assert!((Float::NAN * m).checked_cmp(m).is_err());
+        assert_eq!(
+            (-1.0 * m).checked_cmp(Scalar::ZERO).unwrap(),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        assert!((5.0 * m).is_positive());
+        assert!((-5.0 * m).is_negative());
+        assert!(!(0.0 * kg).is_positive() && !(0.0 * kg).is_negative());
+        assert_eq!((-2.0 * s).signum(), -1.0);
+        assert_eq!((3.0 * m / s).signum(), 1.0);
+        assert!((5.0 * m) > Scalar::ZERO * m);
+    }
+
+    #[test]
+    fn test_compare_with_dimensionless_zero() {
+        assert!((5.0 * m) > Scalar::ZERO);
+        assert!((-5.0 * m) < Scalar::ZERO);
+        assert!(Scalar::ZERO < 2.0 * kg);
+        assert!((0.0 * s) < 1.0 * m);
+        assert_eq!(0.0 * m, Scalar::ZERO);
+        assert_eq!((0.0 * m).partial_cmp(&Scalar::ZERO), Some(Ordering::Equal));
+        assert_ne!(1.0 * m, 1.0 * s);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot compare scalars with different dimensions")]
+    fn test_compare_dimension_mismatch() {
+        let _ = (5.0 * m) > (1.0 * s);
+    }
+
+    #[test]
+    fn test_value_in() {
+        assert_eq!((3.0 * m).value_in(m).unwrap(), 3.0);
+        assert_eq!((1500.0 * m).value_in(1000.0 * m).unwrap(), 1.5);
+        assert!((3.0 * m).value_in(s).is_err());
+    }
+
+    #[test]
+    fn test_total_cmp() {
+        let mut values = [3.0, Float::NAN, -1.0, 2.0, Float::NAN, 0.0].map(|x| x * m);
+        values.sort_by(|a, b| a.total_cmp(*b).unwrap());
+        assert_eq!(values[..4], [-1.0, 0.0, 2.0, 3.0].map(|x| x * m));
+        assert!(values[4..].iter().all(|x| x.is_nan()));
+        assert!(m.total_cmp(s).is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!((1.23456 * m).format(4), "1.235 L");
+        assert_eq!((9.9996 * m).format(4), "10.00 L");
+        assert_eq!((1234.5 * s).format(2), "1.2e3 T");
+        assert_eq!(Scalar::from(0.5).format(3), "0.500");
+        assert_eq!((2.5 * m / s).to_string(), format!("2.5 {}", (m / s).dim()));
+        assert_eq!(format!("{:.1}", 2.25 * kg), "2.2 M");
+    }
+
+    #[test]
+    fn test_cbrt() {
+        let side: Scalar = (8.0 * m.powi(3)).cbrt();
+        assert_eq!(side.dim(), m.dim());
+        assert!((side - 2.0 * m).abs() < 1e-6 * m);
+    }
+
+    #[test]
+    fn test_si_prefix() {
+        assert_eq!((1.0 * m) * SIPrefix::k, 1000.0 * m);
+        assert_eq!(SIPrefix::k * (1.0 * m), 1000.0 * m);
+        assert_eq!((1.0 * m) / SIPrefix::c, 100.0 * m);
+    }
+
+    #[test]
+    fn test_lerp() {
+        let (a, b) = (2.0 * m, 6.0 * m);
+        let mid = a.lerp(b, 0.5);
+        assert_eq!(mid, 4.0 * m);
+        assert_eq!(mid.inverse_lerp(a, b), 0.5);
+        assert_eq!(a.lerp(b, 0.25).inverse_lerp(a, b), 0.25);
+    }
+
+    #[test]
+    fn test_range_diagnostics() {
+        Scalar::take_range_diagnostics();
+        let mass = 1e30 * kg;
+        let energy = mass * (3e8 * m / s).squared();
+        assert!(energy.is_infinite());
+        let tiny = (1e-30 * m) * (1e-30 * m);
+        assert!(tiny.is_zero());
+        let _ = 2.0 * m * 3.0;
+
+        let diagnostics = Scalar::take_range_diagnostics();
+        if cfg!(debug_assertions) {
+            assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+            assert_eq!(diagnostics[0].error, RangeError::NonFinite);
+            assert_eq!(diagnostics[0].operation, '*');
+            assert_eq!(diagnostics[0].location.file(), file!());
+            assert_eq!(diagnostics[1].error, RangeError::Underflow);
+        } else {
+            assert!(diagnostics.is_empty());
+        }
+        assert!(Scalar::take_range_diagnostics().is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+use std::ops::{Add, Sub};
+
+/// Running sum of scalars or vectors with Kahan compensation: the low order bits lost by each
+/// addition are carried into the next one, so the error doesn't grow with the number of terms
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompensatedSum<T> {
+    sum: T,
+    compensation: T,
+    compensated: bool,
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T>> CompensatedSum<T> {
+    /// An empty sum, `zero` sets the dimension of the terms
+    pub fn new(zero: T) -> CompensatedSum<T> {
+        CompensatedSum {
+            sum: zero,
+            compensation: zero,
+            compensated: true,
+        }
+    }
+
+    /// An empty sum adding terms the plain way, to switch compensation off
+    /// without a second code path
+    pub fn naive(zero: T) -> CompensatedSum<T> {
+        CompensatedSum {
+            compensated: false,
+            ..CompensatedSum::new(zero)
+        }
+    }
+
+    /// Compensated sum of every term of `terms`, in order
+    pub fn of(zero: T, terms: impl IntoIterator<Item = T>) -> T {
+        let mut sum = CompensatedSum::new(zero);
+        sum.extend(terms);
+        sum.total()
+    }
+
+    pub fn add(&mut self, term: T) {
+        if !self.compensated {
+            self.sum = self.sum + term;
+            return;
+        }
+        let term = term - self.compensation;
+        let sum = self.sum + term;
+        self.compensation = (sum - self.sum) - term;
+        self.sum = sum;
+    }
+
+    pub fn total(&self) -> T {
+        self.sum
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T>> Extend<T> for CompensatedSum<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, terms: I) {
+        for term in terms {
+            self.add(term);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{units::*, Scalar, Vector};
+
+    #[test]
+    fn test_compensated_sum() {
+        let small = [1e-8, 0.0] * N;
+        let mut compensated = CompensatedSum::new(Vector::zero() * N);
+        let mut naive = CompensatedSum::naive(Vector::zero() * N);
+        compensated.add([1.0, 0.0] * N);
+        naive.add([1.0, 0.0] * N);
+        for _ in 0..1_000_000 {
+            compensated.add(small);
+            naive.add(small);
+        }
+        assert_eq!(naive.total(), [1.0, 0.0] * N);
+        assert!((compensated.total() - [1.01, 0.0] * N).magnitude() < 1e-6 * N);
+        assert_eq!(compensated.total().dim(), N.dim());
+
+        let total = CompensatedSum::of(Scalar::ZERO * kg, [0.1 * kg; 10]);
+        assert!((total - 1.0 * kg).abs() < 1e-6 * kg);
+    }
+}
@@ -1,13 +1,14 @@
 use std::{
-    fmt::Debug,
+    fmt::{Debug, Display},
     ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign},
 };
 
 use macroquad::prelude::{Vec2, Vec3};
 
 use crate::{
-    dimension::{Dimension, DimensionError},
-    Float, Scalar,
+    dimension::{Dimension, DimensionError, SIPrefix},
+    scalar::{format_sig_figs, with_dimension},
+    units, Float, Scalar,
 };
 
 #[derive(Clone, Copy, PartialEq)]
@@ -24,10 +25,30 @@ impl<const N: usize> Vector<N> {
         *self / magnitude
     }
 
+    /// Usable in `const` and `static` items
+    pub const fn new(components: [Float; N], dim: Dimension) -> Vector<N> {
+        Vector(components, dim)
+    }
+
+    /// Rejects NaN and infinite components, for input read from files or the network
+    pub fn new_finite(components: [Float; N], dim: Dimension) -> Result<Vector<N>, DimensionError> {
+        if let Some(x) = components.iter().find(|x| !x.is_finite()) {
+            return Err(DimensionError(format!(
+                "Expected finite components, found {x}"
+            )));
+        }
+        Ok(Vector(components, dim))
+    }
+
     pub const fn zero() -> Vector<N> {
         Vector([0.0; N], Dimension::NONE)
     }
 
+    /// Zero vector of the given dimension, to seed sums
+    pub const fn zero_with_dim(dim: Dimension) -> Vector<N> {
+        Vector([0.0; N], dim)
+    }
+
     pub fn is_zero(&self) -> bool {
         self.0.iter().all(|&x| x.abs() <= Float::EPSILON)
     }
@@ -91,6 +112,18 @@ impl<const N: usize> Vector<N> {
         self.dot(self)
     }
 
+    /// Length of the segment between the points `self` and `other`
+    #[track_caller]
+    pub fn distance(self, other: Vector<N>) -> Scalar {
+        (self - other).magnitude()
+    }
+
+    /// Square of [`Vector::distance`] without the square root, for comparing distances
+    #[track_caller]
+    pub fn distance_squared(self, other: Vector<N>) -> Scalar {
+        (self - other).squared()
+    }
+
     pub const fn as_slice(&self) -> &[Float] {
         &self.0
     }
@@ -109,22 +142,29 @@ impl<const N: usize> Vector<N> {
         Vector(a, Dimension::NONE)
     }
 
+    /// The first `M` components, padded with zeros when `M > N`
     pub fn resize<const M: usize>(&self) -> Vector<M> {
-        if M < N {
-            let mut new = [0.0; M];
-            for (i, &x) in self.0.iter().enumerate() {
-                new[i] = x;
-            }
-            Vector(new, self.1)
-        } else {
-            Vector(unsafe { std::mem::transmute_copy(&self.0) }, self.1)
-        }
+        let mut new = [0.0; M];
+        let n = N.min(M);
+        new[..n].copy_from_slice(&self.0[..n]);
+        Vector(new, self.1)
     }
 
     pub fn project(self, on: Vector<N>) -> Self {
         self.dot(on) / on.magnitude() * on.normalized()
     }
 
+    /// Mirror image across the surface with the given normal, like a velocity bouncing
+    /// elastically off a wall. Only the direction of `normal` matters
+    #[track_caller]
+    pub fn reflect(self, normal: Vector<N>) -> Vector<N> {
+        if normal.magnitude().value() < Float::EPSILON {
+            panic!("Cannot reflect across a zero normal {normal}");
+        }
+        let n = normal.normalized();
+        self - 2.0 * self.dot(n) * n
+    }
+
     pub fn angle_to(&self, other: Vector<N>) -> Float {
         (self.dot(other) / (self.magnitude() * other.magnitude())).acos()
     }
@@ -136,6 +176,163 @@ impl<const N: usize> Vector<N> {
         v[M] = 1.0;
         v.into()
     }
+
+    /// Relativistic velocity addition `self ⊕ other`: the velocity `other`, measured in a frame
+    /// moving at `self`, as seen from the rest frame
+    #[track_caller]
+    pub fn relativistic_add(self, other: Vector<N>, c: Scalar) -> Vector<N> {
+        let velocity = units::m / units::s;
+        if let Err(err) = self
+            .dimension_err(velocity, "self")
+            .and(other.dimension_err(velocity, "other"))
+            .and(c.dimension_err(velocity, "c"))
+        {
+            panic!("{}", err);
+        }
+
+        let c2 = c.squared();
+        let γ = (1.0 - self.squared() / c2).powf(-0.5);
+        let uv = self.dot(other);
+        (self + other / γ + γ / (1.0 + γ) * uv / c2 * self) / (1.0 + uv / c2)
+    }
+
+    /// Elementwise (Hadamard) product, the dimensions multiply
+    pub fn hadamard(self, other: Vector<N>) -> Vector<N> {
+        let mut result = [0.0; N];
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(&x, &y)| x * y)
+            .zip(result.iter_mut())
+            .for_each(|(new, curr)| *curr = new);
+        Vector(result, self.1 * other.1)
+    }
+
+    /// Elementwise division, the inverse of [`Vector::hadamard`]
+    pub fn component_div(self, other: Vector<N>) -> Vector<N> {
+        let mut result = [0.0; N];
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(&x, &y)| x / y)
+            .zip(result.iter_mut())
+            .for_each(|(new, curr)| *curr = new);
+        Vector(result, self.1 / other.1)
+    }
+
+    /// The components expressed in `unit`, e.g. a position in meters for rendering
+    pub fn to_array_in(&self, unit: Scalar) -> Result<[Float; N], DimensionError> {
+        self.dimension_err(unit, "vector")?;
+        Ok(self.0.map(|x| x / unit.0))
+    }
+
+    /// Elementwise absolute value, keeping the dimension
+    pub fn abs(self) -> Vector<N> {
+        Vector(self.0.map(Float::abs), self.1)
+    }
+
+    /// Applies `f` to every component, keeping the dimension
+    pub fn map(self, f: impl Fn(Float) -> Float) -> Vector<N> {
+        Vector(self.0.map(f), self.1)
+    }
+
+    /// Combines the components of `self` and `other` pairwise with `f`,
+    /// both must have the dimension the result keeps
+    #[track_caller]
+    pub fn zip_with(self, other: Vector<N>, f: impl Fn(Float, Float) -> Float) -> Vector<N> {
+        if self.1 != other.1 {
+            panic!(
+                "Cannot combine vectors with different dimensions: {} and {}",
+                self.1, other.1
+            );
+        }
+        Vector(std::array::from_fn(|i| f(self.0[i], other.0[i])), self.1)
+    }
+
+    /// Elementwise sign as a dimensionless vector, see [`Float::signum`]
+    pub fn signum(self) -> Vector<N> {
+        Vector(self.0.map(Float::signum), Dimension::NONE)
+    }
+
+    /// Whether the vectors point along the same line, in the same or opposite directions.
+    /// `tol` bounds the rejection of one direction from the other, zero vectors are never parallel
+    pub fn is_parallel_to(&self, other: Vector<N>, tol: Float) -> bool {
+        if self.is_zero() || other.is_zero() {
+            return false;
+        }
+        let (a, b) = (self.normalized(), other.normalized());
+        (a - a.dot(b) * b).magnitude().value() < tol
+    }
+
+    /// Whether the directions are perpendicular, `tol` bounds the cosine of the angle between them.
+    /// Zero vectors are never orthogonal
+    pub fn is_orthogonal_to(&self, other: Vector<N>, tol: Float) -> bool {
+        if self.is_zero() || other.is_zero() {
+            return false;
+        }
+        self.normalized().dot(other.normalized()).value().abs() < tol
+    }
+
+    /// Rescales the vector down to `max` if it is longer, keeping its direction.
+    /// The zero vector stays zero, `max` must have the dimension of the vector
+    pub fn clamp_magnitude(self, max: Scalar) -> Result<Vector<N>, DimensionError> {
+        max.dimension_err(self.1, "max")?;
+        if !self.is_zero() && self.magnitude() > max {
+            Ok(self.normalized() * max)
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Rescales the vector to `magnitude` keeping its direction, the zero vector stays zero
+    #[track_caller]
+    pub fn with_magnitude(self, magnitude: Scalar) -> Vector<N> {
+        if self.1 != magnitude.1 {
+            panic!(
+                "Cannot give a vector of dimension {} a magnitude of dimension {}",
+                self.1, magnitude.1
+            )
+        }
+        if self.is_zero() {
+            return self;
+        }
+        self.normalized() * magnitude
+    }
+
+    /// The point a fraction `t` of the way from `self` to `other`, extrapolating outside
+    /// `[0, 1]`. `t = 0` and `t = 1` give back `self` and `other` exactly
+    #[track_caller]
+    pub fn lerp(self, other: Vector<N>, t: Float) -> Vector<N> {
+        let Some(point) = (self * (1.0 - t)).checked_add(other * t) else {
+            panic!(
+                "Cannot add vectors with different dimensions: {} and {}",
+                self.1, other.1
+            )
+        };
+        if t == 0.0 {
+            self
+        } else if t == 1.0 {
+            other
+        } else {
+            point
+        }
+    }
+
+    /// [`Vector::lerp`] with `t` clamped to `[0, 1]`, never leaving the segment
+    #[track_caller]
+    pub fn lerp_clamped(self, other: Vector<N>, t: Float) -> Vector<N> {
+        self.lerp(other, t.clamp(0.0, 1.0))
+    }
+
+    /// Every component rounded to `sig_figs` significant figures followed by the dimension
+    pub fn format(&self, sig_figs: usize) -> String {
+        let components = self
+            .0
+            .iter()
+            .map(|&x| format_sig_figs(x, sig_figs))
+            .collect::<Vec<_>>();
+        with_dimension(format!("({})", components.join(", ")), self.1)
+    }
 }
 
 impl Vector<2> {
@@ -146,13 +343,14 @@ impl Vector<2> {
     pub const ZERO: Vector<2> = Vector([0.0, 0.0], Dimension::NONE);
 
     #[track_caller]
-    /// (r, φ)
+    /// (r, φ), the angle of the origin is taken as 0
     pub fn polar_coords(&self) -> (Scalar, Float) {
         let [x, y] = self.0;
         let r = self.magnitude();
-        let φ = if r.abs() <= Float::EPSILON {
-            todo!()
-        } else if y.is_sign_negative() {
+        if r.abs() <= Float::EPSILON {
+            return (Scalar(0.0, self.1), 0.0);
+        }
+        let φ = if y.is_sign_negative() {
             -(x / r).acos()
         } else {
             (x / r).acos()
@@ -164,6 +362,15 @@ impl Vector<2> {
         [r.value() * θ.cos(), r.value() * θ.sin()] * r.dim()
     }
 
+    /// The z component of the cross product of the vectors embedded in 3D,
+    /// positive when `other` is counterclockwise from `self`
+    pub fn cross(&self, other: Vector<2>) -> Scalar {
+        Scalar(
+            self.0[0] * other.0[1] - self.0[1] * other.0[0],
+            self.1 * other.1,
+        )
+    }
+
     pub fn perpendicular(&self, clockwise: bool) -> Self {
         if clockwise {
             Vector([-self.0[1], self.0[0]], self.1)
@@ -208,10 +415,14 @@ impl Vector<3> {
     pub fn spherical_coords(&self) -> (Scalar, Float, Float) {
         let [x, y, z] = self.0;
         let r = self.magnitude();
+        if r.abs() <= Float::EPSILON {
+            return (Scalar(0.0, self.1), 0.0, 0.0);
+        }
         let θ = (z / r).acos();
         let r_xy = x.hypot(y);
+        // On the z axis φ is undefined, θ still tells the poles apart
         let φ = if r_xy.abs() <= Float::EPSILON {
-            todo!()
+            0.0
         } else if y.is_sign_negative() {
             -(x / r_xy).acos()
         } else {
@@ -237,8 +448,9 @@ impl Vector<3> {
 
     #[track_caller]
     pub fn from_cylindrical_coords(ρ: Scalar, φ: Float, z: Float) -> Self {
-        let r = (ρ * ρ + z * z).sqrt();
-        let θ = (z / r).atan();
+        // Polar angle from the +z axis, in [0, π] for negative z too
+        let r = ρ.value().hypot(z);
+        let θ = ρ.value().atan2(z);
         Self::from_spherical_coords(r * ρ.dim(), θ, φ)
     }
 
@@ -266,6 +478,23 @@ impl<const N: usize> Debug for Vector<N> {
     }
 }
 
+impl<const N: usize> Display for Vector<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (i, x) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            Display::fmt(x, f)?;
+        }
+        write!(f, ")")?;
+        if self.1 != Dimension::NONE {
+            write!(f, " {}", self.1)?;
+        }
+        Ok(())
+    }
+}
+
 impl<const N: usize> From<[Float; N]> for Vector<N> {
     fn from(a: [Float; N]) -> Self {
         Vector(a, Dimension::NONE)
@@ -444,6 +673,27 @@ impl<const N: usize> Div<Dimension> for Vector<N> {
     }
 }
 
+impl<const N: usize> Mul<SIPrefix> for Vector<N> {
+    type Output = Vector<N>;
+    fn mul(self, rhs: SIPrefix) -> Self::Output {
+        self * Float::powi(10.0, rhs as _)
+    }
+}
+
+impl<const N: usize> Mul<Vector<N>> for SIPrefix {
+    type Output = Vector<N>;
+    fn mul(self, rhs: Vector<N>) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<const N: usize> Div<SIPrefix> for Vector<N> {
+    type Output = Vector<N>;
+    fn div(self, rhs: SIPrefix) -> Self::Output {
+        self / Float::powi(10.0, rhs as _)
+    }
+}
+
 impl From<Vector<2>> for Vec2 {
     fn from(v: Vector<2>) -> Vec2 {
         Vec2 {
@@ -468,3 +718,251 @@ impl<const N: usize> From<Vector<N>> for Dimension {
         val.1
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::*;
+
+    #[test]
+    fn test_to_array_in() {
+        let position: Vector<2> = [3.0, -4.0] * m;
+        assert_eq!(position.to_array_in(m).unwrap(), [3.0, -4.0]);
+        assert_eq!(position.to_array_in(0.5 * m).unwrap(), [6.0, -8.0]);
+        assert!(position.to_array_in(s).is_err());
+    }
+
+    #[test]
+    fn test_abs_signum() {
+        let v: Vector<2> = [-3.0, 4.0] * m;
+        assert_eq!(v.abs(), [3.0, 4.0] * m);
+        assert_eq!(v.signum(), Vector([-1.0, 1.0], Dimension::NONE));
+    }
+
+    #[test]
+    fn test_cylindrical_round_trip() {
+        for point in [
+            [1.0, 2.0, 3.0],
+            [-1.5, 0.5, -2.0],
+            [0.3, -4.0, 0.0],
+            [-2.0, -2.0, -0.1],
+            [5.0, 1.0, -10.0],
+        ] {
+            let v: Vector<3> = point * m;
+            let (ρ, φ, z) = v.cylindrical_coords();
+            let back = Vector::<3>::from_cylindrical_coords(ρ, φ, z);
+            assert_eq!(back.dim(), m.dim());
+            assert!((back - v).magnitude() < 1e-5 * m, "{v:?} {back:?}");
+        }
+    }
+
+    #[test]
+    fn test_hadamard() {
+        let a: Vector<2> = [2.0, 3.0] * m;
+        let b = [4.0, 5.0] / s;
+        assert_eq!(a.hadamard(b), [8.0, 15.0] * (m / s));
+        assert_eq!(a.hadamard(b).component_div(b), a);
+    }
+
+    #[test]
+    fn test_relativistic_add() {
+        let c = crate::constants::c;
+        let v: Vector<3> = 0.6 * c * Vector::<3>::i;
+        let w = v.relativistic_add(v, c);
+        assert!((w.magnitude() / c - 0.6 * 2.0 / 1.36).abs() < 1e-4);
+        assert!(w.magnitude() < c);
+    }
+
+    #[test]
+    fn test_const_new() {
+        static WIND: Vector<2> = Vector::new([3.0, 4.0], m.dim().div(s.dim()));
+        assert_eq!(WIND, [3.0, 4.0] * m / s);
+        assert_eq!(WIND.magnitude(), 5.0 * m / s);
+    }
+
+    #[test]
+    fn test_new_finite() {
+        let v = Vector::new_finite([1.0, 2.0, 3.0], m.dim()).unwrap();
+        assert_eq!(v, [1.0, 2.0, 3.0] * m);
+        assert!(Vector::new_finite([1.0, Float::NAN], m.dim()).is_err());
+        assert!(Vector::new_finite([Float::INFINITY, 0.0], m.dim()).is_err());
+    }
+
+    #[test]
+    fn test_zero_with_dim() {
+        let zero = Vector::<3>::zero_with_dim((m / s).dim());
+        assert!(zero.is_zero());
+        assert_eq!(zero.dim(), (m / s).dim());
+        let sum = [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+            .iter()
+            .fold(zero, |acc, &v| acc + v * (m / s));
+        assert_eq!(sum, [5.0, 7.0, 9.0] * (m / s));
+    }
+
+    #[test]
+    fn test_parallel_orthogonal() {
+        let a: Vector<3> = [1.0, 2.0, 3.0] * m;
+        let b: Vector<3> = [2.0, 4.0, 6.0] * (m / s);
+        assert!(a.is_parallel_to(b, 1e-6));
+        assert!(a.is_parallel_to(-a, 1e-6));
+        assert!(!a.is_orthogonal_to(b, 1e-6));
+
+        let c: Vector<3> = [3.0, 0.0, -1.0] * m;
+        assert!(a.is_orthogonal_to(c, 1e-6));
+        assert!(!a.is_parallel_to(c, 1e-6));
+
+        let zero = Vector::<3>::zero() * m;
+        assert!(!zero.is_parallel_to(a, 1e-6) && !a.is_parallel_to(zero, 1e-6));
+        assert!(!zero.is_orthogonal_to(a, 1e-6) && !a.is_orthogonal_to(zero, 1e-6));
+    }
+
+    #[test]
+    fn test_si_prefix() {
+        let v: Vector<3> = [1.0, -2.0, 0.5] * m;
+        assert_eq!(v * SIPrefix::k, [1000.0, -2000.0, 500.0] * m);
+        assert_eq!(SIPrefix::k * v, v * SIPrefix::k);
+        assert!((v / SIPrefix::m - v * SIPrefix::k).magnitude() < 1e-3 * m);
+    }
+
+    #[test]
+    fn test_clamp_magnitude() {
+        let max = 5.0 * m / s;
+        let short: Vector<2> = [3.0, 0.0] * (m / s);
+        assert_eq!(short.clamp_magnitude(max).unwrap(), short);
+
+        let long: Vector<2> = [30.0, 40.0] * (m / s);
+        assert_eq!(long.clamp_magnitude(max).unwrap(), [3.0, 4.0] * (m / s));
+        assert!(long.clamp_magnitude(5.0 * m).is_err());
+        assert_eq!(short.with_magnitude(max), [5.0, 0.0] * (m / s));
+
+        let zero = Vector::<2>::zero() * (m / s);
+        assert_eq!(zero.clamp_magnitude(max).unwrap(), zero);
+        assert_eq!(zero.with_magnitude(max), zero);
+    }
+
+    #[test]
+    fn test_format() {
+        let v: Vector<2> = [1.23456, -0.5] * m;
+        assert_eq!(v.format(3), "(1.23, -0.500) L");
+        assert_eq!(v.to_string(), "(1.23456, -0.5) L");
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = [0.1, -3.0, 7.0] * m;
+        let b = [2.0, 5.0, -1.0 / 3.0] * m;
+        assert_eq!(
+            a.lerp(b, 0.0).0.map(Float::to_bits),
+            a.0.map(Float::to_bits)
+        );
+        assert_eq!(
+            a.lerp(b, 1.0).0.map(Float::to_bits),
+            b.0.map(Float::to_bits)
+        );
+
+        let middle = a.lerp(b, 0.5);
+        assert_eq!(middle.dim(), m.dim());
+        assert!((middle - (a + b) / 2.0).magnitude() < 1e-6 * m);
+        assert!((a.lerp(b, 2.0) - (2.0 * b - a)).magnitude() < 1e-5 * m);
+
+        assert_eq!(a.lerp_clamped(b, 2.0), b);
+        assert_eq!(a.lerp_clamped(b, -1.0), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot add vectors with different dimensions")]
+    fn test_lerp_dimension_mismatch() {
+        let _ = ([1.0, 0.0] * m).lerp([1.0, 0.0] * s, 0.5);
+    }
+
+    #[test]
+    fn test_reflect() {
+        let v = [1.0, -1.0] * m / s;
+        assert_eq!(v.reflect(Vector::<2>::j), [1.0, 1.0] * m / s);
+        assert_eq!(v.reflect([0.0, -3.0] * m), [1.0, 1.0] * m / s);
+        let diagonal = ([2.0, 0.0, 0.0] * N).reflect([1.0, 1.0, 0.0].into());
+        assert_eq!(diagonal.dim(), N.dim());
+        assert!((diagonal - [0.0, -2.0, 0.0] * N).magnitude() < 1e-6 * N);
+    }
+
+    #[test]
+    #[should_panic(expected = "zero normal")]
+    fn test_reflect_zero_normal() {
+        let _ = ([1.0, -1.0] * m / s).reflect(Vector::zero());
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = [1.0, 2.0, 3.0] * m;
+        let b = [4.0, 6.0, 3.0] * m;
+        assert_eq!(a.distance(b), 5.0 * m);
+        assert_eq!(b.distance(a), 5.0 * m);
+        assert_eq!(a.distance_squared(b), 25.0 * m * m);
+        assert_eq!(a.distance(a), 0.0 * m);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot subtract vectors with different dimensions")]
+    fn test_distance_dimension_mismatch() {
+        let _ = ([1.0, 0.0] * m).distance_squared([1.0, 0.0] * s);
+    }
+
+    #[test]
+    fn test_map_zip_with() {
+        let v = [1.4, -2.6, 3.5] * m;
+        assert_eq!(v.map(Float::floor), [1.0, -3.0, 3.0] * m);
+        assert_eq!(v.map(|x| x.clamp(-1.0, 1.0)), [1.0, -1.0, 1.0] * m);
+
+        let w = [2.0, -5.0, 3.0] * m;
+        assert_eq!(v.zip_with(w, Float::max), [2.0, -2.6, 3.5] * m);
+        assert_eq!(v.zip_with(w, Float::min).dim(), m.dim());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot combine vectors with different dimensions")]
+    fn test_zip_with_dimension_mismatch() {
+        let _ = ([1.0, 0.0] * m).zip_with([1.0, 0.0] * s, Float::max);
+    }
+
+    #[test]
+    fn test_resize() {
+        let v = [1.0, -2.0] * m;
+        assert_eq!(v.resize::<4>(), [1.0, -2.0, 0.0, 0.0] * m);
+        assert_eq!(v.resize::<2>(), v);
+        assert_eq!(([1.0, 2.0, 3.0] * s).resize::<2>(), [1.0, 2.0] * s);
+    }
+
+    #[test]
+    fn test_coords_at_origin_and_poles() {
+        assert_eq!(
+            Vector::<2>::zero().polar_coords(),
+            (Scalar(0.0, Dimension::NONE), 0.0)
+        );
+        assert_eq!((Vector::<2>::zero() * m).polar_coords(), (0.0 * m, 0.0));
+        assert_eq!(
+            (Vector::<3>::zero() * m).spherical_coords(),
+            (0.0 * m, 0.0, 0.0)
+        );
+
+        assert_eq!(
+            ([0.0, 0.0, 2.0] * m).spherical_coords(),
+            (2.0 * m, 0.0, 0.0)
+        );
+        let (r, θ, φ) = ([0.0, 0.0, -2.0] * m).spherical_coords();
+        assert_eq!((r, φ), (2.0 * m, 0.0));
+        assert!((θ - crate::PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cross_2d() {
+        let r = [2.0, 0.0] * m;
+        let force = [1.0, 3.0] * N;
+        assert_eq!(r.cross(force), 6.0 * N * m);
+        assert_eq!(force.cross(r), -6.0 * N * m);
+        assert_eq!(r.cross(r), 0.0 * m * m);
+
+        let r3 = [2.0, 0.0, 0.0] * m;
+        let force3 = [1.0, 3.0, 0.0] * N;
+        assert_eq!(r3.cross(force3)[2], r.cross(force).value());
+    }
+}
@@ -0,0 +1,164 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use macroquad::color::Color;
+
+use crate::{
+    dimension::DimensionError, units, Float, Object, ObjectBuilder, Scalar, Universe, Vector,
+};
+
+const HEADER: &str = "x,y,z,vx,vy,vz,mass,charge,radius,r,g,b,a";
+
+impl Universe<3> {
+    /// Writes one CSV row per object after a header naming the columns: position in m,
+    /// velocity in m/s, mass in kg, charge in C, radius in m and the color as RGBA from 0 to 1.
+    ///
+    /// Only these properties are kept, fields, planes, springs and attributes are not
+    pub fn export_scene_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{HEADER}")?;
+        for object in self.objects() {
+            let (x, v, color) = (object.position(), object.velocity(), object.color());
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                x[0],
+                x[1],
+                x[2],
+                v[0],
+                v[1],
+                v[2],
+                object.mass().value(),
+                object.charge().value(),
+                object.size().value(),
+                color.r,
+                color.g,
+                color.b,
+                color.a
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Builds a universe holding the objects of a scene written by
+    /// [`Universe::export_scene_csv`], objects of zero mass come back massless
+    pub fn import_scene_csv(reader: impl Read) -> io::Result<Universe<3>> {
+        let mut lines = BufReader::new(reader).lines();
+        match lines.next().transpose()? {
+            Some(header) if header.trim() == HEADER => {}
+            _ => return Err(invalid_data(&format!("Expected the header `{HEADER}`"))),
+        }
+
+        let mut universe = Universe::new();
+        for (row, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let values = line
+                .split(',')
+                .map(|value| value.trim().parse::<Float>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| invalid_data(&format!("Row {}: {e}", row + 1)))?;
+            let Ok(values) = <[Float; 13]>::try_from(values) else {
+                return Err(invalid_data(&format!(
+                    "Row {}: expected 13 values",
+                    row + 1
+                )));
+            };
+            let object =
+                parse_object(values).map_err(|e| invalid_data(&format!("Row {}: {e}", row + 1)))?;
+            universe.add_object(object);
+        }
+        Ok(universe)
+    }
+}
+
+/// Rejects NaN and infinite values, which `parse` accepts
+fn parse_object(
+    [x, y, z, vx, vy, vz, mass, charge, radius, r, g, b, a]: [Float; 13],
+) -> Result<Object<3>, DimensionError> {
+    if let Some(c) = [r, g, b, a].into_iter().find(|c| !c.is_finite()) {
+        return Err(DimensionError(format!(
+            "Expected a finite color component, found {c}"
+        )));
+    }
+    let builder = ObjectBuilder::new_at(Vector::new_finite([x, y, z], units::m.dim())?)
+        .with_velocity(Vector::new_finite(
+            [vx, vy, vz],
+            (units::m / units::s).dim(),
+        )?)
+        .with_charge(Scalar::new_finite(charge, units::C.dim())?)
+        .with_size(Scalar::new_finite(radius, units::m.dim())?)
+        .with_color(Color::new(r, g, b, a));
+    let builder = if mass == 0.0 {
+        builder.massless()
+    } else {
+        builder.with_mass(Scalar::new_finite(mass, units::kg.dim())?)
+    };
+    builder.build()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::*;
+    use macroquad::color::{RED, SKYBLUE};
+
+    #[test]
+    fn test_scene_csv_round_trip() {
+        let mut universe = Universe::<3>::new();
+        universe.add_object(
+            ObjectBuilder::new_at([0.1, 2.0, -3.5] * m)
+                .with_velocity([1.0, 0.0, 0.25] * m / s)
+                .with_mass(2.5 * kg)
+                .build()
+                .unwrap(),
+        );
+        universe.add_object(
+            ObjectBuilder::new_at([10.0, 0.0, 0.0] * m)
+                .with_charge(1e-6 * C)
+                .with_size(0.3 * m)
+                .with_color(RED)
+                .build()
+                .unwrap(),
+        );
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, -7.0, 1.0 / 3.0] * m)
+                .with_mass(1e24 * kg)
+                .with_size(6.4e6 * m)
+                .with_color(SKYBLUE)
+                .build()
+                .unwrap(),
+        );
+
+        let mut csv = Vec::new();
+        universe.export_scene_csv(&mut csv).unwrap();
+        let imported = Universe::<3>::import_scene_csv(csv.as_slice()).unwrap();
+        assert_eq!(imported.objects().len(), 3);
+        for (a, b) in universe.objects().iter().zip(imported.objects()) {
+            assert!((a.position() - b.position()).magnitude() < 1e-6 * m);
+            assert!((a.velocity() - b.velocity()).magnitude() < 1e-6 * m / s);
+            assert!((a.mass() - b.mass()).abs() <= 1e-6 * a.mass());
+            assert_eq!(a.charge(), b.charge());
+            assert_eq!(a.size(), b.size());
+            assert_eq!(a.color(), b.color());
+        }
+
+        assert!(Universe::<3>::import_scene_csv(&b"x,y\n1,2"[..]).is_err());
+        let short = format!("{HEADER}\n1,2,3\n");
+        assert!(Universe::<3>::import_scene_csv(short.as_bytes()).is_err());
+        for row in [
+            "NaN,0,0,0,0,0,1,0,1,1,1,1,1",
+            "0,0,0,inf,0,0,1,0,1,1,1,1,1",
+            "0,0,0,0,0,0,-inf,0,1,1,1,1,1",
+            "0,0,0,0,0,0,1,NaN,1,1,1,1,1",
+            "0,0,0,0,0,0,1,0,1,1,NaN,1,1",
+        ] {
+            let csv = format!("{HEADER}\n{row}\n");
+            assert!(Universe::<3>::import_scene_csv(csv.as_bytes()).is_err());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::{ObjectID, Scalar, Universe, Vector};
+
+/// Positions and velocities of every object at one instant, to compare a [`Universe`] against
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniverseSnapshot<const N: usize> {
+    pub time: Scalar,
+    /// ID of each object, indexed like [`Universe::objects`]
+    pub ids: Vec<ObjectID>,
+    /// Position and velocity of each object, indexed like [`Universe::objects`]
+    pub states: Vec<(Vector<N>, Vector<N>)>,
+}
+
+/// A change to one object between a snapshot and the current state
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObjectDelta<const N: usize> {
+    Added {
+        id: ObjectID,
+        position: Vector<N>,
+        velocity: Vector<N>,
+    },
+    Removed {
+        id: ObjectID,
+    },
+    Moved {
+        id: ObjectID,
+        position: Vector<N>,
+        velocity: Vector<N>,
+    },
+}
+
+impl<const N: usize> Universe<N> {
+    pub fn snapshot(&self) -> UniverseSnapshot<N> {
+        UniverseSnapshot {
+            time: self.time(),
+            ids: self.ids().to_vec(),
+            states: self
+                .objects()
+                .iter()
+                .map(|o| (o.position(), o.velocity()))
+                .collect(),
+        }
+    }
+
+    /// Objects added, removed or moved since `previous`, with the change in position and
+    /// velocity of the moved ones, so only what changed has to be sent over a network.
+    ///
+    /// Objects are matched by ID, the added and moved ones come in the order of
+    /// [`Universe::objects`] and the removed ones last
+    pub fn diff(&self, previous: &UniverseSnapshot<N>) -> Vec<ObjectDelta<N>> {
+        let before = previous
+            .ids
+            .iter()
+            .copied()
+            .zip(previous.states.iter().copied())
+            .collect::<HashMap<_, _>>();
+        let mut deltas = Vec::new();
+        for (&id, object) in self.ids().iter().zip(self.objects()) {
+            let (position, velocity) = (object.position(), object.velocity());
+            match before.get(&id) {
+                None => deltas.push(ObjectDelta::Added {
+                    id,
+                    position,
+                    velocity,
+                }),
+                Some(&(old_position, old_velocity))
+                    if old_position != position || old_velocity != velocity =>
+                {
+                    deltas.push(ObjectDelta::Moved {
+                        id,
+                        position: position - old_position,
+                        velocity: velocity - old_velocity,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        deltas.extend(
+            previous
+                .ids
+                .iter()
+                .filter(|&&id| self.object(id).is_none())
+                .map(|&id| ObjectDelta::Removed { id }),
+        );
+        deltas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{units::*, ObjectBuilder};
+
+    #[test]
+    fn test_diff() {
+        let mut universe = Universe::<2>::new();
+        let ids = [0.0, 2.0, 4.0]
+            .map(|x| universe.add_object(ObjectBuilder::new_at([x, 0.0] * m).build().unwrap()));
+        let snapshot = universe.snapshot();
+        assert!(universe.diff(&snapshot).is_empty());
+
+        universe.set_position(ids[1], [2.0, 3.0] * m);
+        let added = universe.add_object(
+            ObjectBuilder::new_at([6.0, 0.0] * m)
+                .with_velocity([1.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            universe.diff(&snapshot),
+            vec![
+                ObjectDelta::Moved {
+                    id: ids[1],
+                    position: [0.0, 3.0] * m,
+                    velocity: [0.0, 0.0] * m / s,
+                },
+                ObjectDelta::Added {
+                    id: added,
+                    position: [6.0, 0.0] * m,
+                    velocity: [1.0, 0.0] * m / s,
+                },
+            ]
+        );
+
+        let snapshot = universe.snapshot();
+        universe.delete_object(added);
+        assert_eq!(
+            universe.diff(&snapshot),
+            vec![ObjectDelta::Removed { id: added }]
+        );
+
+        // The objects after a deleted one keep their IDs, so they don't show up as moved
+        let snapshot = universe.snapshot();
+        universe.delete_object(ids[0]);
+        assert_eq!(
+            universe.diff(&snapshot),
+            vec![ObjectDelta::Removed { id: ids[0] }]
+        );
+    }
+}
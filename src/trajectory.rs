@@ -0,0 +1,272 @@
+use std::io::{self, Read, Write};
+
+use crate::{dimension::Dimension, units, Float, Scalar, Universe, Vector};
+
+const MAGIC: &[u8; 4] = b"OGTR";
+/// The counts in a file aren't trusted, larger frames grow as their positions are read
+const MAX_PREALLOCATED_POSITIONS: usize = 4096;
+
+/// Positions of every object at one instant
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrajectoryFrame<const N: usize> {
+    pub time: Scalar,
+    pub positions: Vec<Vector<N>>,
+}
+
+/// What the header of a binary trajectory describes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrajectoryHeader {
+    /// Number of components of every position
+    pub components: usize,
+    /// Objects in the first frame, later frames carry their own count
+    pub object_count: usize,
+    pub time_dimension: Dimension,
+    pub position_dimension: Dimension,
+}
+
+/// Collects the positions of a [`Universe`] over time.
+///
+/// The binary format is little endian: the magic `OGTR`, the header as `u32` components,
+/// `u32` object count and the time and position dimensions as 7 `i32` exponents each,
+/// then every frame as its `f32` time, `u32` object count and `f32` coordinates.
+#[derive(Clone, Debug, Default)]
+pub struct TrajectoryRecorder<const N: usize> {
+    frames: Vec<TrajectoryFrame<N>>,
+}
+
+impl<const N: usize> TrajectoryRecorder<N> {
+    pub fn new() -> TrajectoryRecorder<N> {
+        TrajectoryRecorder { frames: Vec::new() }
+    }
+
+    /// Appends the current positions of the objects in `universe`
+    pub fn record(&mut self, universe: &Universe<N>) {
+        self.frames.push(TrajectoryFrame {
+            time: universe.time(),
+            positions: universe.objects().iter().map(|o| o.position()).collect(),
+        });
+    }
+
+    pub fn frames(&self) -> &[TrajectoryFrame<N>] {
+        &self.frames
+    }
+
+    pub fn header(&self) -> TrajectoryHeader {
+        let first = self.frames.first();
+        TrajectoryHeader {
+            components: N,
+            object_count: first.map_or(0, |frame| frame.positions.len()),
+            time_dimension: first.map_or(units::s.dim(), |frame| frame.time.dim()),
+            position_dimension: first
+                .and_then(|frame| frame.positions.first())
+                .map_or(units::m.dim(), |position| position.dim()),
+        }
+    }
+
+    pub fn write_binary(&self, mut writer: impl Write) -> io::Result<()> {
+        let header = self.header();
+        writer.write_all(MAGIC)?;
+        write_u32(&mut writer, header.components)?;
+        write_u32(&mut writer, header.object_count)?;
+        write_dimension(&mut writer, header.time_dimension)?;
+        write_dimension(&mut writer, header.position_dimension)?;
+        for frame in &self.frames {
+            writer.write_all(&frame.time.value().to_le_bytes())?;
+            write_u32(&mut writer, frame.positions.len())?;
+            for position in &frame.positions {
+                for x in position.0 {
+                    writer.write_all(&x.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the header of a binary trajectory, leaving `reader` at the first frame
+    pub fn read_header(mut reader: impl Read) -> io::Result<TrajectoryHeader> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a binary trajectory"));
+        }
+        let header = TrajectoryHeader {
+            components: read_u32(&mut reader)?,
+            object_count: read_u32(&mut reader)?,
+            time_dimension: read_dimension(&mut reader)?,
+            position_dimension: read_dimension(&mut reader)?,
+        };
+        if header.components != N {
+            return Err(invalid_data(&format!(
+                "Expected a trajectory of {N} components, found {}",
+                header.components
+            )));
+        }
+        Ok(header)
+    }
+
+    pub fn read_binary(mut reader: impl Read) -> io::Result<TrajectoryRecorder<N>> {
+        let header = Self::read_header(&mut reader)?;
+        let mut frames = Vec::new();
+        loop {
+            // Only running out between frames ends the trajectory, a cut frame is an error
+            let mut time = [0; 4];
+            let read = loop {
+                match reader.read(&mut time[..1]) {
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    result => break result?,
+                }
+            };
+            if read == 0 {
+                break;
+            }
+            reader.read_exact(&mut time[1..])?;
+            let count = read_u32(&mut reader)?;
+            let mut positions = Vec::with_capacity(count.min(MAX_PREALLOCATED_POSITIONS));
+            for _ in 0..count {
+                let mut position = [0.0; N];
+                for x in position.iter_mut() {
+                    *x = read_f32(&mut reader)?;
+                }
+                positions.push(Vector(position, header.position_dimension));
+            }
+            frames.push(TrajectoryFrame {
+                time: Scalar(Float::from_le_bytes(time), header.time_dimension),
+                positions,
+            });
+        }
+        Ok(TrajectoryRecorder { frames })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_u32(writer: &mut impl Write, n: usize) -> io::Result<()> {
+    let n = u32::try_from(n).map_err(|_| invalid_data("Count doesn't fit in a u32"))?;
+    writer.write_all(&n.to_le_bytes())
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<usize> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes) as usize)
+}
+
+fn read_f32(reader: &mut impl Read) -> io::Result<Float> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(Float::from_le_bytes(bytes))
+}
+
+fn write_dimension(writer: &mut impl Write, dim: Dimension) -> io::Result<()> {
+    for exponent in [
+        dim.time,
+        dim.length,
+        dim.mass,
+        dim.electric_current,
+        dim.thermodynamic_temperature,
+        dim.amount_of_substance,
+        dim.luminous_intensity,
+    ] {
+        writer.write_all(&exponent.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_dimension(reader: &mut impl Read) -> io::Result<Dimension> {
+    let mut exponents = [0; 7];
+    for exponent in exponents.iter_mut() {
+        let mut bytes = [0; 4];
+        reader.read_exact(&mut bytes)?;
+        *exponent = i32::from_le_bytes(bytes);
+    }
+    let [time, length, mass, electric_current, thermodynamic_temperature, amount_of_substance, luminous_intensity] =
+        exponents;
+    Ok(Dimension {
+        time,
+        length,
+        mass,
+        electric_current,
+        thermodynamic_temperature,
+        amount_of_substance,
+        luminous_intensity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{units::*, ObjectBuilder};
+
+    fn recorder() -> TrajectoryRecorder<2> {
+        let mut universe = Universe::<2>::new();
+        universe.add_gravitational_field([0.0, -9.8] * N / kg);
+        for x in [0.0, 3.0, 6.0] {
+            universe.add_object(
+                ObjectBuilder::new_at([x, 0.0] * m)
+                    .with_velocity([1.0, x] * m / s)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let mut recorder = TrajectoryRecorder::new();
+        for _ in 0..5 {
+            universe.step(0.01);
+            recorder.record(&universe);
+        }
+        recorder
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let recorder = recorder();
+        let mut buffer = Vec::new();
+        recorder.write_binary(&mut buffer).unwrap();
+        let read = TrajectoryRecorder::<2>::read_binary(buffer.as_slice()).unwrap();
+
+        assert_eq!(read.frames().len(), 5);
+        for (a, b) in recorder.frames().iter().zip(read.frames()) {
+            assert_eq!(a.time.value().to_bits(), b.time.value().to_bits());
+            assert_eq!(a.time.dim(), b.time.dim());
+            for (x, y) in a.positions.iter().zip(&b.positions) {
+                assert_eq!(x.0.map(Float::to_bits), y.0.map(Float::to_bits));
+                assert_eq!(x.dim(), y.dim());
+            }
+        }
+    }
+
+    #[test]
+    fn test_binary_header() {
+        let mut buffer = Vec::new();
+        recorder().write_binary(&mut buffer).unwrap();
+        let header = TrajectoryRecorder::<2>::read_header(buffer.as_slice()).unwrap();
+        assert_eq!(header.components, 2);
+        assert_eq!(header.object_count, 3);
+        assert_eq!(header.time_dimension, s.dim());
+        assert_eq!(header.position_dimension, m.dim());
+
+        assert!(TrajectoryRecorder::<3>::read_binary(buffer.as_slice()).is_err());
+        assert!(TrajectoryRecorder::<2>::read_binary(&b"nope"[..]).is_err());
+    }
+
+    #[test]
+    fn test_binary_truncated() {
+        let mut buffer = Vec::new();
+        recorder().write_binary(&mut buffer).unwrap();
+        let frame = 4 + 4 + 3 * 2 * 4;
+        let end = buffer.len();
+        for cut in [end - 1, end - frame + 2, end - frame + 5] {
+            let error = TrajectoryRecorder::<2>::read_binary(&buffer[..cut]).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        }
+        let read = TrajectoryRecorder::<2>::read_binary(&buffer[..end - frame]).unwrap();
+        assert_eq!(read.frames().len(), 4);
+
+        // A frame claiming u32::MAX positions fails on the missing data, not the allocation
+        buffer.extend((0.0 as Float).to_le_bytes());
+        buffer.extend(u32::MAX.to_le_bytes());
+        let error = TrajectoryRecorder::<2>::read_binary(buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,148 +1,3860 @@
 #![allow(non_snake_case)]
+use std::{
+    collections::HashSet,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
 use crate::{
-    collision::possible_collisions, constants, h, units, Float, Object, ObjectID, Vector, STEP,
+    collision::{possible_collisions, sweep_and_prune, Contact},
+    coulomb_force,
+    dimension::{Dimension, DimensionError},
+    gravitational_force, h, units, BoundingBox, Collider, CompensatedSum, Float, Object,
+    ObjectBuilder, ObjectID, Scalar, ScalarField, Vector, STEP,
 };
 
 pub struct Universe<const N: usize> {
     objects: Vec<Object<N>>,
+    /// ID of each object, indexed like `objects`
+    ids: Vec<ObjectID>,
+    /// Generation of each ID slot and the index of its object, `None` once removed
+    slots: Vec<(u32, Option<usize>)>,
+    /// Slots of removed objects, the last one freed is reused first
+    free_slots: Vec<usize>,
+    /// Static colliders, objects bounce off them as if they had infinite mass
+    planes: Vec<Collider<N>>,
+    springs: Vec<Spring>,
+    /// Objects whose positions are recorded after every step, with their recent samples
+    tracked: Vec<(ObjectID, Vec<(Scalar, Vector<N>)>)>,
+    /// Number of samples kept per tracked object
+    trajectory_capacity: usize,
     field_g: Vector<N>,
     field_E: Vector<N>,
     field_B: Vector<N>,
+    uniform_force: Vector<N>,
+    /// Angular velocity of the frame the objects are simulated in
+    rotation: Vector<3>,
+    accumulator: Float,
+    substeps: u64,
+    /// Simulated seconds, summed in `f64` so tiny substeps keep adding up
+    time: f64,
+    adaptive_step: Option<AdaptiveStep>,
+    paused: bool,
+    /// Velocities are clamped to this magnitude after every substep
+    speed_limit: Option<Scalar>,
+    /// Steps per substep for objects flagged `fast`
+    fast_subdivisions: usize,
+    /// Speed below which objects fall asleep and how long they have to stay below it
+    sleeping: Option<(Scalar, Scalar)>,
+    solver_iterations: usize,
+    /// Broad phase subsets at most this large are tested pairwise
+    broadphase_leaf_size: usize,
+    /// Object indices sorted along x, kept between substeps by the incremental broad phase
+    broadphase_order: Option<Vec<usize>>,
+    contact_solver: ContactSolver,
+    deepest_contacts_first: bool,
+    /// Contacts found in the last substep, restitution only applies to the ones that just formed
+    touching: HashSet<Touching>,
+    /// Whether pair forces are accumulated with Kahan summation
+    compensated_summation: bool,
+    frame: Frame,
+    recording: bool,
+    events: Vec<UniverseEvent<N>>,
+    verify_collisions: bool,
+    collision_errors: Vec<CollisionVerificationError>,
+    /// Relative kinetic energy above which collisions break objects, and into how many pieces
+    fragmentation: Option<(Scalar, usize)>,
+    /// State of the xorshift generator scattering fragments
+    rng: u64,
+}
+
+/// A contact between two objects, lower index first, or between an object and a plane
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Touching {
+    Objects(usize, usize),
+    Plane(usize, usize),
+}
+
+/// Frame the equations of motion are integrated in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Frame {
+    #[default]
+    World,
+    /// Moves with the center of mass during each `step`, which keeps the numbers small
+    /// for systems translating far from the origin. The shift is Galilean, so it is only
+    /// exact well below the speed of light.
+    CenterOfMomentum,
+}
+
+/// Coordinate plane a 3D universe is projected onto for drawing, the depth is the remaining
+/// coordinate and grows away from the viewer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Plane {
+    /// `(x, y)` at depth `z`
+    #[default]
+    XY,
+    /// `(y, z)` at depth `x`
+    YZ,
+    /// `(z, x)` at depth `y`
+    ZX,
+}
+
+impl Plane {
+    /// Coordinates of `point` in the plane and its depth
+    pub fn project(&self, point: Vector<3>) -> (Vector<2>, Scalar) {
+        let [x, y, z] = point.0;
+        let (u, v, depth) = match self {
+            Plane::XY => (x, y, z),
+            Plane::YZ => (y, z, x),
+            Plane::ZX => (z, x, y),
+        };
+        (Vector([u, v], point.dim()), Scalar(depth, point.dim()))
+    }
+}
+
+/// The settings of a [`Universe`], everything but its contents
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UniverseConfig<const N: usize> {
+    pub gravitational_field: Vector<N>,
+    pub electric_field: Vector<N>,
+    pub magnetic_field: Vector<N>,
+    pub uniform_force: Vector<N>,
+    pub rotating_frame: Vector<3>,
+    /// `None` steps in fixed substeps of `STEP`
+    pub adaptive_step: Option<AdaptiveStep>,
+    /// `None` lets numerical error push objects to or past the speed of light
+    pub speed_limit: Option<Scalar>,
+    pub fast_subdivisions: usize,
+    /// Linear speed threshold and time, see [`Universe::set_sleeping`]
+    pub sleeping: Option<(Scalar, Scalar)>,
+    pub frame: Frame,
+    pub solver_iterations: usize,
+    pub contact_solver: ContactSolver,
+    pub deepest_contacts_first: bool,
+    pub compensated_summation: bool,
+    pub broadphase_leaf_size: usize,
+    pub incremental_broadphase: bool,
+    pub verify_collisions: bool,
+}
+
+impl<const N: usize> Default for UniverseConfig<N> {
+    fn default() -> Self {
+        Self {
+            gravitational_field: Vector::zero() * units::N / units::kg,
+            electric_field: Vector::zero() * units::N / units::C,
+            magnetic_field: Vector::zero() * units::T,
+            uniform_force: Vector::zero() * units::N,
+            rotating_frame: Vector::zero() * units::rad / units::s,
+            adaptive_step: None,
+            speed_limit: Some((1.0 - 1e-5) * crate::constants::c),
+            fast_subdivisions: 1,
+            sleeping: None,
+            frame: Frame::World,
+            solver_iterations: 1,
+            contact_solver: ContactSolver::Sequential,
+            deepest_contacts_first: false,
+            compensated_summation: false,
+            broadphase_leaf_size: 32,
+            incremental_broadphase: false,
+            verify_collisions: false,
+        }
+    }
+}
+
+/// Chooses each substep from the largest acceleration `a_max` as `dt = √(softening / a_max)`,
+/// clamped between `min` and `max`, so close encounters get resolved finely
+/// while quiet stretches are crossed in few substeps
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveStep {
+    /// Length scale over which the acceleration may change within a substep
+    pub softening: Scalar,
+    pub min: Scalar,
+    pub max: Scalar,
+}
+
+/// How the contacts of a substep are solved in each solver iteration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContactSolver {
+    /// Each contact sees the impulses of the contacts solved before it, converges fast
+    /// but the outcome of pile-ups depends on the order objects were added in
+    #[default]
+    Sequential,
+    /// Every contact is solved from the same velocities and the impulses are applied together,
+    /// so the outcome doesn't depend on insertion order. Needs more solver iterations to converge
+    Simultaneous,
+}
+
+/// Something a ray can hit, planes are identified by the order they were added in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColliderID {
+    Object(ObjectID),
+    Plane(usize),
+}
+
+/// Hookean spring between the objects at indices `a` and `b`
+#[derive(Clone, Copy, Debug)]
+struct Spring {
+    a: usize,
+    b: usize,
+    rest_length: Scalar,
+    stiffness: Scalar,
+}
+
+/// A collision response that broke a conservation law, found by collision verification
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollisionVerificationError {
+    /// The kinetic energy of the colliding objects, spin included, grew although no
+    /// restitution exceeded 1
+    EnergyIncrease {
+        time: Scalar,
+        before: Scalar,
+        after: Scalar,
+    },
+    /// The total momentum of the colliding objects changed by `change`
+    MomentumChange { time: Scalar, change: Scalar },
+}
+
+/// A mutating call made on a [`Universe`] while recording inputs
+#[derive(Clone, Debug)]
+pub struct UniverseEvent<const N: usize> {
+    /// Simulated time at which the call was made
+    pub time: Scalar,
+    pub action: UniverseAction<N>,
+}
+
+#[derive(Clone, Debug)]
+pub enum UniverseAction<const N: usize> {
+    Step(Float),
+    StepOnce,
+    Paused(bool),
+    AddObject(Box<Object<N>>),
+    DeleteObject(ObjectID),
+    /// Which objects were kept by `remove_objects`
+    RetainObjects(Vec<bool>),
+    Clear,
+    AddPlane(Vector<N>, Scalar),
+    AddSpring(ObjectID, ObjectID, Scalar),
+    Track(ObjectID),
+    TrajectoryCapacity(usize),
+    GravitationalField(Vector<N>),
+    ElectricField(Vector<N>),
+    MagneticField(Vector<N>),
+    UniformForce(Vector<N>),
+    RotatingFrame(Vector<3>),
+    SolverIterations(usize),
+    BroadphaseLeafSize(usize),
+    IncrementalBroadphase(bool),
+    ContactSolver(ContactSolver),
+    DeepestContactsFirst(bool),
+    CompensatedSummation(bool),
+    AdaptiveStep(Option<AdaptiveStep>),
+    SpeedLimit(Option<Scalar>),
+    FastSubdivisions(usize),
+    Sleeping(bool, Scalar, Scalar),
+    Restitution(ObjectID, Float),
+    Mass(ObjectID, Scalar),
+    Position(ObjectID, Vector<N>),
+    Velocity(ObjectID, Vector<N>),
+    Impulse(ObjectID, Vector<N>),
+    RadialImpulse(Vector<N>, Scalar, Scalar),
+    ReferenceFrame(Frame),
+    VerifyCollisions(bool),
+    Fragmentation(Scalar, usize),
 }
 
 impl<const N: usize> Universe<N> {
     pub fn new() -> Universe<N> {
+        let config = UniverseConfig::default();
         Universe {
             objects: Vec::new(),
-            field_g: Vector::zero() * units::N / units::kg,
-            field_E: Vector::zero() * units::N / units::C,
-            field_B: Vector::zero() * units::T,
+            ids: Vec::new(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+            planes: Vec::new(),
+            springs: Vec::new(),
+            tracked: Vec::new(),
+            trajectory_capacity: 1000,
+            field_g: config.gravitational_field,
+            field_E: config.electric_field,
+            field_B: config.magnetic_field,
+            uniform_force: config.uniform_force,
+            rotation: config.rotating_frame,
+            accumulator: 0.0,
+            substeps: 0,
+            time: 0.0,
+            adaptive_step: config.adaptive_step,
+            paused: false,
+            speed_limit: config.speed_limit,
+            fast_subdivisions: config.fast_subdivisions,
+            sleeping: config.sleeping,
+            solver_iterations: config.solver_iterations,
+            broadphase_leaf_size: config.broadphase_leaf_size,
+            broadphase_order: config.incremental_broadphase.then(Vec::new),
+            contact_solver: config.contact_solver,
+            deepest_contacts_first: config.deepest_contacts_first,
+            touching: HashSet::new(),
+            compensated_summation: config.compensated_summation,
+            frame: config.frame,
+            recording: false,
+            events: Vec::new(),
+            verify_collisions: config.verify_collisions,
+            collision_errors: Vec::new(),
+            fragmentation: None,
+            rng: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    #[track_caller]
+    pub fn with_config(config: UniverseConfig<N>) -> Universe<N> {
+        let mut universe = Self::new();
+        universe.set_config(config);
+        universe
+    }
+
+    pub fn config(&self) -> UniverseConfig<N> {
+        UniverseConfig {
+            gravitational_field: self.field_g,
+            electric_field: self.field_E,
+            magnetic_field: self.field_B,
+            uniform_force: self.uniform_force,
+            rotating_frame: self.rotation,
+            adaptive_step: self.adaptive_step,
+            speed_limit: self.speed_limit,
+            fast_subdivisions: self.fast_subdivisions,
+            sleeping: self.sleeping,
+            frame: self.frame,
+            solver_iterations: self.solver_iterations,
+            contact_solver: self.contact_solver,
+            deepest_contacts_first: self.deepest_contacts_first,
+            compensated_summation: self.compensated_summation,
+            broadphase_leaf_size: self.broadphase_leaf_size,
+            incremental_broadphase: self.broadphase_order.is_some(),
+            verify_collisions: self.verify_collisions,
+        }
+    }
+
+    /// Applies every setting through its setter, so the dimensions are checked
+    /// and each change is recorded
+    #[track_caller]
+    pub fn set_config(&mut self, config: UniverseConfig<N>) {
+        self.add_gravitational_field(config.gravitational_field);
+        self.add_electric_field(config.electric_field);
+        self.add_magnetic_field(config.magnetic_field);
+        self.set_uniform_force(config.uniform_force);
+        self.set_rotating_frame(config.rotating_frame);
+        self.set_adaptive_step(config.adaptive_step);
+        self.set_speed_limit(config.speed_limit);
+        self.set_fast_subdivisions(config.fast_subdivisions);
+        match config.sleeping {
+            Some((threshold, time)) => self.set_sleeping(true, threshold, time),
+            None => self.set_sleeping(false, 0.0 * units::m / units::s, 0.0 * units::s),
+        }
+        self.set_reference_frame(config.frame);
+        self.set_solver_iterations(config.solver_iterations);
+        self.set_contact_solver(config.contact_solver);
+        self.set_deepest_contacts_first(config.deepest_contacts_first);
+        self.set_compensated_summation(config.compensated_summation);
+        self.set_broadphase_leaf_size(config.broadphase_leaf_size);
+        self.set_incremental_broadphase(config.incremental_broadphase);
+        self.set_verify_collisions(config.verify_collisions);
+    }
+
+    /// Builds a fresh universe by applying recorded events in order
+    pub fn replay(events: impl IntoIterator<Item = UniverseEvent<N>>) -> Universe<N> {
+        let mut universe = Self::new();
+        for event in events {
+            match event.action {
+                UniverseAction::Step(dt) => universe.step(dt),
+                UniverseAction::StepOnce => universe.step_once(),
+                UniverseAction::Paused(true) => universe.pause(),
+                UniverseAction::Paused(false) => universe.resume(),
+                UniverseAction::AddObject(object) => {
+                    universe.add_object(*object);
+                }
+                UniverseAction::DeleteObject(id) => {
+                    universe.delete_object(id);
+                }
+                UniverseAction::RetainObjects(keep) => {
+                    let mut keep = keep.into_iter();
+                    universe.remove_objects(|_| keep.next().unwrap_or(true));
+                }
+                UniverseAction::Clear => universe.clear(),
+                UniverseAction::AddPlane(normal, offset) => universe.add_plane(normal, offset),
+                UniverseAction::AddSpring(a, b, stiffness) => universe.add_spring(a, b, stiffness),
+                UniverseAction::Track(id) => universe.track(id),
+                UniverseAction::TrajectoryCapacity(capacity) => {
+                    universe.set_trajectory_capacity(capacity)
+                }
+                UniverseAction::GravitationalField(g) => universe.add_gravitational_field(g),
+                UniverseAction::ElectricField(E) => universe.add_electric_field(E),
+                UniverseAction::MagneticField(B) => universe.add_magnetic_field(B),
+                UniverseAction::UniformForce(force) => universe.set_uniform_force(force),
+                UniverseAction::RotatingFrame(ω) => universe.set_rotating_frame(ω),
+                UniverseAction::SolverIterations(n) => universe.set_solver_iterations(n),
+                UniverseAction::BroadphaseLeafSize(n) => universe.set_broadphase_leaf_size(n),
+                UniverseAction::IncrementalBroadphase(enabled) => {
+                    universe.set_incremental_broadphase(enabled)
+                }
+                UniverseAction::ContactSolver(solver) => universe.set_contact_solver(solver),
+                UniverseAction::DeepestContactsFirst(enabled) => {
+                    universe.set_deepest_contacts_first(enabled)
+                }
+                UniverseAction::CompensatedSummation(enabled) => {
+                    universe.set_compensated_summation(enabled)
+                }
+                UniverseAction::AdaptiveStep(adaptive) => universe.set_adaptive_step(adaptive),
+                UniverseAction::SpeedLimit(limit) => universe.set_speed_limit(limit),
+                UniverseAction::FastSubdivisions(n) => universe.set_fast_subdivisions(n),
+                UniverseAction::Sleeping(enabled, threshold, time) => {
+                    universe.set_sleeping(enabled, threshold, time)
+                }
+                UniverseAction::Restitution(id, e) => universe.set_restitution(id, e),
+                UniverseAction::Mass(id, mass) => universe.set_mass(id, mass),
+                UniverseAction::Position(id, position) => universe.set_position(id, position),
+                UniverseAction::Velocity(id, velocity) => universe.set_velocity(id, velocity),
+                UniverseAction::Impulse(id, impulse) => universe.apply_impulse(id, impulse),
+                UniverseAction::RadialImpulse(center, strength, radius) => {
+                    universe.apply_radial_impulse(center, strength, radius)
+                }
+                UniverseAction::ReferenceFrame(frame) => universe.set_reference_frame(frame),
+                UniverseAction::VerifyCollisions(enabled) => {
+                    universe.set_verify_collisions(enabled)
+                }
+                UniverseAction::Fragmentation(threshold, pieces) => {
+                    universe.set_fragmentation(threshold, pieces)
+                }
+            }
+        }
+        universe
+    }
+
+    /// While enabled, every mutating call is logged so it can be passed to [`Universe::replay`]
+    pub fn record_inputs(&mut self, enabled: bool) {
+        self.recording = enabled;
+    }
+
+    /// Takes the events recorded so far
+    pub fn take_events(&mut self) -> Vec<UniverseEvent<N>> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn record(&mut self, action: UniverseAction<N>) {
+        if self.recording {
+            self.events.push(UniverseEvent {
+                time: self.time(),
+                action,
+            });
+        }
+    }
+
+    /// Simulated time elapsed
+    pub fn time(&self) -> Scalar {
+        self.time as Float * units::s
+    }
+
+    /// Number of substeps taken so far
+    pub fn substeps(&self) -> u64 {
+        self.substeps
+    }
+
+    /// Hash of the positions and velocities of every object, bit for bit
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for object in &self.objects {
+            for x in object.position.0.iter().chain(object.velocity.0.iter()) {
+                x.to_bits().hash(&mut hasher);
+            }
         }
+        hasher.finish()
     }
 
     pub fn add_gravitational_field(&mut self, g: Vector<N>) {
+        self.record(UniverseAction::GravitationalField(g));
         self.field_g = g;
     }
 
+    /// Sets the gravitational field to standard gravity along +y (down in screen coordinates),
+    /// or along x for a 1D universe
+    pub fn set_earth_gravity(&mut self) {
+        let down = if N > 1 { 1 } else { 0 };
+        self.add_gravitational_field(crate::g[1] * Vector::basis(down) * crate::g.dim());
+    }
+
     pub fn add_electric_field(&mut self, E: Vector<N>) {
+        self.record(UniverseAction::ElectricField(E));
         self.field_E = E;
     }
 
     pub fn add_magnetic_field(&mut self, B: Vector<N>) {
+        self.record(UniverseAction::MagneticField(B));
         self.field_B = B;
     }
 
+    /// Force applied to every object regardless of its mass or charge, like wind
+    #[track_caller]
+    pub fn set_uniform_force(&mut self, force: Vector<N>) {
+        if let Err(e) = force.dimension_err(units::N, "force") {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::UniformForce(force));
+        self.uniform_force = force;
+    }
+
+    /// Number of passes over all contacts when resolving collisions (at least 1, default 1).
+    /// More iterations let stacks and chains of touching objects converge.
+    pub fn set_solver_iterations(&mut self, iterations: usize) {
+        self.record(UniverseAction::SolverIterations(iterations));
+        self.solver_iterations = iterations.max(1);
+    }
+
+    /// Below this many objects the broad phase stops splitting and tests every pair,
+    /// which is faster than recursing for small sets (at least 2, default 32).
+    /// Doesn't change which pairs are found. The default comes from
+    /// `cargo bench --bench broadphase`: on sparse and dense crowds alike, 32 to 64 beat
+    /// smaller leaves, which recurse too deep, and larger ones, which test too many pairs
+    pub fn set_broadphase_leaf_size(&mut self, leaf_size: usize) {
+        self.record(UniverseAction::BroadphaseLeafSize(leaf_size));
+        self.broadphase_leaf_size = leaf_size.max(2);
+    }
+
+    /// Sweeps along x keeping the sorted order between substeps instead of splitting
+    /// from scratch, which pays off for crowds of slow objects. Finds the same pairs.
+    /// `cargo bench --bench broadphase` has it about 2.5 times faster on 4000 objects drifting at 1 m/s
+    pub fn set_incremental_broadphase(&mut self, enabled: bool) {
+        self.record(UniverseAction::IncrementalBroadphase(enabled));
+        if enabled != self.broadphase_order.is_some() {
+            self.broadphase_order = enabled.then(Vec::new);
+        }
+    }
+
+    /// Pairs of objects whose bounding boxes overlap
+    fn broad_phase(&mut self) -> Vec<(usize, usize)> {
+        match &mut self.broadphase_order {
+            Some(order) => sweep_and_prune(&self.objects, order),
+            None => possible_collisions(&self.objects, self.broadphase_leaf_size),
+        }
+    }
+
+    pub fn set_contact_solver(&mut self, solver: ContactSolver) {
+        self.record(UniverseAction::ContactSolver(solver));
+        self.contact_solver = solver;
+    }
+
+    /// Solves the contacts of each substep from the deepest penetration to the shallowest
+    /// instead of in broad phase order. With the sequential solver this makes pile-ups with
+    /// distinct depths come out the same whatever order the objects were added in
+    pub fn set_deepest_contacts_first(&mut self, enabled: bool) {
+        self.record(UniverseAction::DeepestContactsFirst(enabled));
+        self.deepest_contacts_first = enabled;
+    }
+
+    /// Accumulates the gravity and Coulomb forces on each object with Kahan summation.
+    /// Pairs are always summed in the order of the objects, whatever the broad phase, so the
+    /// same scene gives the same bits; compensation additionally keeps the many small pulls of
+    /// a large crowd from vanishing into the rounding of a dominant one
+    pub fn set_compensated_summation(&mut self, enabled: bool) {
+        self.record(UniverseAction::CompensatedSummation(enabled));
+        self.compensated_summation = enabled;
+    }
+
+    /// Simulates in a frame spinning at the angular velocity `ω` around the origin, adding the
+    /// centrifugal force `-m ω×(ω×r)` and the Coriolis force `-2m ω×v` to every object.
+    /// In 2D only the z component of `ω` matters
+    #[track_caller]
+    pub fn set_rotating_frame(&mut self, ω: Vector<3>) {
+        if let Err(e) = ω.dimension_err(units::rad / units::s, "ω") {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::RotatingFrame(ω));
+        self.rotation = ω;
+    }
+
+    /// Clamps the speed of every massive object to `limit` after each substep, so numerical
+    /// error can't push it to the speed of light where the Lorentz factor blows up.
+    /// Defaults to just below `c`, `None` disables the clamp
+    #[track_caller]
+    pub fn set_speed_limit(&mut self, limit: Option<Scalar>) {
+        if let Some(limit) = limit {
+            if let Err(e) = limit.dimension_err(units::m / units::s, "limit") {
+                panic!("{e}")
+            }
+            if limit.is_nan() || limit <= 0.0 {
+                panic!("{}", DimensionError::new("Speed limit must be positive"))
+            }
+        }
+        self.record(UniverseAction::SpeedLimit(limit));
+        self.speed_limit = limit;
+    }
+
+    /// Splits each substep into this many steps for objects with the `fast` attribute
+    /// (at least 1, default 1). Forces on fast objects are recomputed at every step with the
+    /// other objects moving linearly across the substep, so light objects in tight orbits
+    /// get resolved finely without refining the whole simulation
+    pub fn set_fast_subdivisions(&mut self, subdivisions: usize) {
+        self.record(UniverseAction::FastSubdivisions(subdivisions));
+        self.fast_subdivisions = subdivisions.max(1);
+    }
+
+    /// Puts objects to sleep once they have been slower than `linear_threshold` for `time`.
+    /// Sleeping objects are neither integrated nor tested against each other, and act as
+    /// immovable in contacts, until something touching them moves at least as fast as the
+    /// threshold, an object touching them is removed or they are moved by hand.
+    /// Changing the fields doesn't wake them.
+    /// Disabling sleeping wakes every object
+    #[track_caller]
+    pub fn set_sleeping(&mut self, enabled: bool, linear_threshold: Scalar, time: Scalar) {
+        if let Err(e) = linear_threshold
+            .dimension_err(units::m / units::s, "linear_threshold")
+            .and(time.dimension_err(units::s, "time"))
+        {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::Sleeping(enabled, linear_threshold, time));
+        self.sleeping = enabled.then_some((linear_threshold, time));
+        if !enabled {
+            self.objects.iter_mut().for_each(Object::wake);
+        }
+    }
+
+    /// `None` goes back to fixed substeps of `STEP`
+    #[track_caller]
+    pub fn set_adaptive_step(&mut self, adaptive: Option<AdaptiveStep>) {
+        if let Some(adaptive) = adaptive {
+            if let Err(e) = adaptive
+                .softening
+                .dimension_err(units::m, "softening")
+                .and(adaptive.min.dimension_err(units::s, "min"))
+                .and(adaptive.max.dimension_err(units::s, "max"))
+            {
+                panic!("{e}")
+            }
+            assert!(
+                0.0 < adaptive.min && adaptive.min <= adaptive.max,
+                "Adaptive step bounds must satisfy 0 < min <= max"
+            );
+        }
+        self.record(UniverseAction::AdaptiveStep(adaptive));
+        self.adaptive_step = adaptive;
+    }
+
+    pub fn set_reference_frame(&mut self, frame: Frame) {
+        self.record(UniverseAction::ReferenceFrame(frame));
+        self.frame = frame;
+    }
+
+    /// Checks that every collision response keeps momentum and doesn't create kinetic energy,
+    /// meant for debugging as it copies every velocity each substep.
+    /// Momentum is not checked in substeps where an object touches a plane.
+    pub fn set_verify_collisions(&mut self, enabled: bool) {
+        self.record(UniverseAction::VerifyCollisions(enabled));
+        self.verify_collisions = enabled;
+    }
+
+    /// Breaks the lighter object of a collision into `pieces` fragments when the kinetic energy
+    /// of the approach along the contact normal exceeds `threshold`.
+    /// Fragments share the mass, charge and volume of the object and fly apart in pseudo-random
+    /// directions with the energy above the threshold, conserving momentum. They keep the
+    /// orientation and spin of the object and start spaced by their radii.
+    /// The fragments are added after the other objects with new IDs.
+    #[track_caller]
+    pub fn set_fragmentation(&mut self, threshold: Scalar, pieces: usize) {
+        if let Err(e) = threshold.dimension_err(units::J, "threshold") {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::Fragmentation(threshold, pieces));
+        self.fragmentation = Some((threshold, pieces));
+    }
+
+    /// Violations found since the last call while verifying collisions
+    pub fn take_collision_errors(&mut self) -> Vec<CollisionVerificationError> {
+        std::mem::take(&mut self.collision_errors)
+    }
+
+    /// First object or plane hit by the ray from `origin` along `direction` within `max_distance`,
+    /// and the distance to it. `skip` is ignored, so rays can be cast from inside an object.
+    /// Panics if `direction` is zero
+    #[track_caller]
+    pub fn raycast(
+        &self,
+        origin: Vector<N>,
+        direction: Vector<N>,
+        max_distance: Scalar,
+        skip: Option<ObjectID>,
+    ) -> Option<(ColliderID, Scalar)> {
+        if let Err(e) = origin
+            .dimension_err(units::m, "origin")
+            .and(max_distance.dimension_err(units::m, "max distance"))
+        {
+            panic!("{e}")
+        }
+        if direction.is_zero() {
+            panic!("Cannot cast a ray along a zero direction")
+        }
+        let direction = direction.normalized();
+        let objects = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| skip != Some(self.ids[i]))
+            .map(|(i, object)| (ColliderID::Object(self.ids[i]), object.collider()));
+        let planes = self
+            .planes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| (ColliderID::Plane(i), plane.clone()));
+        objects
+            .chain(planes)
+            .filter_map(|(id, collider)| Some((id, collider.raycast(origin, direction)?)))
+            .filter(|&(_, distance)| distance <= max_distance)
+            .min_by(|(_, a), (_, b)| a.value().total_cmp(&b.value()))
+    }
+
+    /// Gap between the bottom of an object and whatever lies below it, along the gravitational
+    /// field. `None` if nothing is within `max_distance` or there is no gravity to define down
+    #[track_caller]
+    pub fn raycast_down(&self, id: ObjectID, max_distance: Scalar) -> Option<(ColliderID, Scalar)> {
+        if self.field_g.is_zero() {
+            return None;
+        }
+        let object = &self.objects[self.index_of(id)];
+        let (hit, distance) = self.raycast(
+            object.position,
+            self.field_g,
+            max_distance + object.size(),
+            Some(id),
+        )?;
+        Some((hit, distance - object.size()))
+    }
+
+    /// Object whose surface is closest to `point` and the distance to that surface,
+    /// negative when `point` is inside the object.
+    /// With the incremental broad phase only the objects near `point` along x are measured
+    #[track_caller]
+    pub fn nearest_object(&self, point: Vector<N>) -> Option<(ObjectID, Scalar)> {
+        if let Err(e) = point.dimension_err(units::m, "point") {
+            panic!("{e}")
+        }
+        let distance = |i: usize| point.distance(self.objects[i].position) - self.objects[i].size();
+        match &self.broadphase_order {
+            Some(order) if order.len() == self.objects.len() => self.nearest_along_x(point, order),
+            _ => (0..self.objects.len())
+                .map(|i| (i, distance(i)))
+                .min_by(|(_, a), (_, b)| a.value().total_cmp(&b.value()))
+                .map(|(i, _)| i),
+        }
+        .map(|i| (self.ids[i], distance(i)))
+    }
+
+    /// Scans outward from `point` in the x order the incremental broad phase keeps, which
+    /// objects have barely moved out of since the last substep. The scan stops on each side
+    /// once the x extents alone put the objects further than the nearest one so far
+    fn nearest_along_x(&self, point: Vector<N>, order: &[usize]) -> Option<usize> {
+        let objects = &self.objects;
+        let left = |i: usize| objects[i].position[0] - objects[i].size().value();
+        let distance = |i: usize| (point.distance(objects[i].position) - objects[i].size()).value();
+
+        let mut order = order.to_vec();
+        for i in 1..order.len() {
+            let mut j = i;
+            while j > 0 && left(order[j - 1]).total_cmp(&left(order[j])).is_gt() {
+                order.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+        let largest = objects
+            .iter()
+            .map(|o| o.size().value())
+            .fold(0.0, Float::max);
+
+        // Objects to the right are at least as far as their left edge, those to the left
+        // may reach back over at most the largest diameter
+        let x = point[0];
+        let start = order.partition_point(|&i| left(i) < x);
+        let mut nearest = (None, Float::INFINITY);
+        for &i in &order[start..] {
+            if left(i) - x >= nearest.1 {
+                break;
+            }
+            if distance(i) < nearest.1 {
+                nearest = (Some(i), distance(i));
+            }
+        }
+        for &i in order[..start].iter().rev() {
+            if x - left(i) - 2.0 * largest >= nearest.1 {
+                break;
+            }
+            if distance(i) < nearest.1 {
+                nearest = (Some(i), distance(i));
+            }
+        }
+        // Diverged objects are only the nearest when there is nothing else
+        nearest.0.or(order.first().copied())
+    }
+
+    /// Mass weighted mean position, zero for an empty universe
+    pub fn center_of_mass(&self) -> Vector<N> {
+        let mass = self.total_mass();
+        if mass.is_zero() {
+            return Vector::zero() * units::m;
+        }
+        CompensatedSum::of(
+            Vector::zero() * units::m * units::kg,
+            self.objects.iter().map(|o| o.position * o.mass()),
+        ) / mass
+    }
+
+    /// Mass weighted mean velocity, zero for an empty universe
+    pub fn center_of_mass_velocity(&self) -> Vector<N> {
+        let mass = self.total_mass();
+        if mass.is_zero() {
+            return Vector::zero() * units::m / units::s;
+        }
+        self.total_momentum() / mass
+    }
+
+    /// Sum of `mv` over every object
+    pub fn total_momentum(&self) -> Vector<N> {
+        CompensatedSum::of(
+            Vector::zero() * units::kg * units::m / units::s,
+            self.objects.iter().map(|o| o.velocity * o.mass()),
+        )
+    }
+
+    /// Sum of the relativistic kinetic energies of every object
+    pub fn total_kinetic_energy(&self) -> Scalar {
+        CompensatedSum::of(
+            Scalar::ZERO * units::J,
+            self.objects.iter().map(|o| o.kinetic_energy()),
+        )
+    }
+
+    pub fn total_charge(&self) -> Scalar {
+        CompensatedSum::of(
+            Scalar::ZERO * units::C,
+            self.objects.iter().map(|o| o.charge()),
+        )
+    }
+
+    /// Coulomb potential of every charged object in volts, its gradient is in V/m
+    pub fn electric_potential(&self) -> ScalarField<'_, N> {
+        let potential: ScalarField<'_, N> = (
+            |x: Vector<N>| {
+                self.objects
+                    .iter()
+                    .fold(Scalar::ZERO * units::V, |acc, object| {
+                        acc + crate::constants::k_e() * object.charge()
+                            / x.distance(object.position)
+                    })
+            },
+            units::V,
+        )
+            .into();
+        debug_assert_eq!(potential.gradient().dim(), (units::V / units::m).dim());
+        potential
+    }
+
+    /// Newtonian potential of every object in J/kg, its gradient is in m/s²
+    pub fn gravitational_potential(&self) -> ScalarField<'_, N> {
+        let potential: ScalarField<'_, N> = (
+            |x: Vector<N>| {
+                self.objects
+                    .iter()
+                    .fold(Scalar::ZERO * units::J / units::kg, |acc, object| {
+                        acc - crate::constants::G * object.mass() / x.distance(object.position)
+                    })
+            },
+            units::J / units::kg,
+        )
+            .into();
+        debug_assert_eq!(
+            potential.gradient().dim(),
+            (units::m / units::s.powi(2)).dim()
+        );
+        potential
+    }
+
+    /// Whether the net charge is within `tol` of zero
+    #[track_caller]
+    pub fn is_neutral(&self, tol: Scalar) -> bool {
+        self.total_charge().abs() <= tol
+    }
+
+    fn total_mass(&self) -> Scalar {
+        CompensatedSum::of(
+            Scalar::ZERO * units::kg,
+            self.objects.iter().map(|o| o.mass()),
+        )
+    }
+
     pub fn objects(&self) -> &[Object<N>] {
         &self.objects
     }
 
-    pub fn add_object(&mut self, object: Object<N>) -> ObjectID {
+    /// `None` once the object has been removed
+    pub fn object(&self, id: ObjectID) -> Option<&Object<N>> {
+        self.index(id).map(|i| &self.objects[i])
+    }
+
+    /// IDs of the objects, in the order of [`Universe::objects`]
+    pub fn ids(&self) -> &[ObjectID] {
+        &self.ids
+    }
+
+    /// IDs of every object matching `predicate`, in the order of [`Universe::objects`]
+    pub fn find(&self, predicate: impl Fn(&Object<N>) -> bool) -> Vec<ObjectID> {
+        (0..self.objects.len())
+            .filter(|&i| predicate(&self.objects[i]))
+            .map(|i| self.ids[i])
+            .collect()
+    }
+
+    /// ID of the first object matching `predicate`
+    pub fn find_one(&self, predicate: impl Fn(&Object<N>) -> bool) -> Option<ObjectID> {
+        let i = self.objects.iter().position(predicate)?;
+        Some(self.ids[i])
+    }
+
+    /// Index in `objects` of the object with this ID, if it is still there
+    fn index(&self, id: ObjectID) -> Option<usize> {
+        match self.slots.get(id.slot) {
+            Some(&(generation, index)) if generation == id.generation => index,
+            _ => None,
+        }
+    }
+
+    #[track_caller]
+    fn index_of(&self, id: ObjectID) -> usize {
+        match self.index(id) {
+            Some(i) => i,
+            None => panic!("No object with ID {id:?}"),
+        }
+    }
+
+    /// Appends `object` under a free slot, or a new one
+    fn push_object(&mut self, object: Object<N>) -> ObjectID {
+        let index = self.objects.len();
+        let id = match self.free_slots.pop() {
+            Some(slot) => {
+                self.slots[slot].1 = Some(index);
+                ObjectID {
+                    slot,
+                    generation: self.slots[slot].0,
+                }
+            }
+            None => {
+                self.slots.push((0, Some(index)));
+                ObjectID {
+                    slot: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        };
         self.objects.push(object);
-        ObjectID(self.objects.len() - 1)
+        self.ids.push(id);
+        id
+    }
+
+    /// Invalidates the ID of a removed object, its slot comes back with the next generation
+    fn free_slot(&mut self, id: ObjectID) {
+        let slot = &mut self.slots[id.slot];
+        *slot = (slot.0.wrapping_add(1), None);
+        self.free_slots.push(id.slot);
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Removes every object, the fields are kept
+    pub fn clear(&mut self) {
+        self.record(UniverseAction::Clear);
+        for id in std::mem::take(&mut self.ids) {
+            self.free_slot(id);
+        }
+        self.objects.clear();
+        self.springs.clear();
+        self.tracked.clear();
+        self.touching.clear();
+    }
+
+    pub fn add_object(&mut self, object: Object<N>) -> ObjectID {
+        if self.recording {
+            self.record(UniverseAction::AddObject(Box::new(object.clone())));
+        }
+        self.push_object(object)
     }
 
+    /// Panics if the object was already removed
+    #[track_caller]
     pub fn delete_object(&mut self, object: ObjectID) -> Object<N> {
-        self.objects.remove(object.0)
+        let index = self.index_of(object);
+        self.record(UniverseAction::DeleteObject(object));
+        self.retain_references(
+            &(0..self.objects.len())
+                .map(|i| i != index)
+                .collect::<Vec<_>>(),
+        );
+        self.objects.remove(index)
     }
 
-    pub fn with_objects(&mut self, objects: impl IntoIterator<Item = Object<N>>) -> &mut Self {
-        self.objects.extend(objects);
-        self
+    /// Panics unless the coefficient is within `[0, 1]`, see [`ObjectAttributes::validate`]
+    #[track_caller]
+    pub fn set_restitution(&mut self, object: ObjectID, restitution_coefficient: Float) {
+        let index = self.index_of(object);
+        let mut attributes = self.objects[index].attributes();
+        attributes.restitution_coefficient = restitution_coefficient;
+        if let Err(e) = attributes.validate() {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::Restitution(object, restitution_coefficient));
+        self.objects[index].attributes_mut().restitution_coefficient = restitution_coefficient;
     }
 
-    pub fn remove_objects<F>(&mut self, f: F)
-    where
-        F: FnMut(&Object<N>) -> bool,
-    {
-        self.objects.retain(f);
+    /// Panics unless `mass` is positive, and for massless objects, see [`Object::set_mass`]
+    #[track_caller]
+    pub fn set_mass(&mut self, object: ObjectID, mass: Scalar) {
+        let index = self.index_of(object);
+        if let Err(e) = self.objects[index].set_mass(mass) {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::Mass(object, mass));
     }
 
-    pub fn step(&mut self, dt: Float) {
-        for _ in 0..(dt / STEP) as usize {
-            let f = self.objects.clone();
-            for (i, object) in self.objects.iter_mut().enumerate() {
-                let v = object.velocity + 0.5 * h() * object.acc;
-                object.position += v * h();
-
-                // Calculate force
-                let mut g = f.clone();
-                g[i].position = object.position;
-                let force = Self::force(&g, i, object, self.field_g, self.field_E, self.field_B);
-                object.acc = object.acceleration(force);
+    /// Teleports the object, see [`Object::set_position`]
+    #[track_caller]
+    pub fn set_position(&mut self, object: ObjectID, position: Vector<N>) {
+        let index = self.index_of(object);
+        if let Err(e) = self.objects[index].set_position(position) {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::Position(object, position));
+    }
 
-                object.velocity = v + object.acc * h() * 0.5;
-            }
-            self.resolve_collisions();
+    /// Launches the object, see [`Object::set_velocity`]
+    #[track_caller]
+    pub fn set_velocity(&mut self, object: ObjectID, velocity: Vector<N>) {
+        let index = self.index_of(object);
+        if let Err(e) = self.objects[index].set_velocity(velocity) {
+            panic!("{e}")
         }
+        self.record(UniverseAction::Velocity(object, velocity));
     }
 
-    fn force(
-        f: &[Object<N>],
-        i: usize,
-        object: &Object<N>,
-        g: Vector<N>,
-        E: Vector<N>,
-        B: Vector<N>,
-    ) -> Vector<N> {
-        let mut force = Vector::zero() * units::N;
-        for (j, obj) in f.iter().enumerate() {
-            if j == i {
+    /// Changes the velocity of the object by `impulse / m`, massless objects are left alone
+    #[track_caller]
+    pub fn apply_impulse(&mut self, object: ObjectID, impulse: Vector<N>) {
+        if let Err(e) = impulse.dimension_err(units::kg * units::m / units::s, "impulse") {
+            panic!("{e}")
+        }
+        let index = self.index_of(object);
+        self.record(UniverseAction::Impulse(object, impulse));
+        let object = &mut self.objects[index];
+        if !object.is_massless() {
+            object.velocity += impulse / object.mass();
+            object.wake();
+        }
+    }
+
+    /// Pushes every object within `radius` of `center` straight away from it, like an
+    /// explosion. The impulse is `strength` at the center and falls off linearly to zero
+    /// at `radius`, objects exactly at the center aren't pushed
+    #[track_caller]
+    pub fn apply_radial_impulse(&mut self, center: Vector<N>, strength: Scalar, radius: Scalar) {
+        if let Err(e) = center
+            .dimension_err(units::m, "center")
+            .and(strength.dimension_err(units::kg * units::m / units::s, "strength"))
+            .and(radius.dimension_err(units::m, "radius"))
+        {
+            panic!("{e}")
+        }
+        if radius.is_nan() || radius <= 0.0 {
+            panic!("{}", DimensionError::new("Radius must be positive"))
+        }
+        self.record(UniverseAction::RadialImpulse(center, strength, radius));
+        for object in self.objects.iter_mut() {
+            let offset = object.position - center;
+            let distance = offset.magnitude();
+            if object.is_massless() || offset.is_zero() || distance >= radius {
                 continue;
             }
-            let r1 = object.position();
-            let r = obj.position() - r1;
-            force += r.normalized()
-                * (constants::G * object.mass() * obj.mass()
-                    - constants::k_e() * object.charge() * obj.charge())
-                / r.squared()
-        }
-        force += object.charge() * E + object.mass() * g;
-        let vB = if N == 3 {
-            (object.velocity[1] * B[2] - object.velocity[2] * B[1]) * Vector::basis(0)
-                - (object.velocity[0] * B[2] - object.velocity[2] * B[0]) * Vector::basis(1)
-                + (object.velocity[0] * B[1] - object.velocity[1] * B[0]) * Vector::basis(2)
-        } else {
-            panic!("B field in non 3D space");
-        };
-        force += object.charge() * vB * units::N / units::C;
-        force
+            let impulse = offset.normalized() * strength * (1.0 - (distance / radius).value());
+            object.velocity += impulse / object.mass();
+            object.wake();
+        }
     }
 
-    fn resolve_collisions(&mut self) {
-        let possible_collisions = possible_collisions(&self.objects);
+    /// Adds an infinite static plane bounding the solid half-space `x·normal < offset`,
+    /// `normal` is normalized
+    #[track_caller]
+    pub fn add_plane(&mut self, normal: Vector<N>, offset: Scalar) {
+        if let Err(e) = normal
+            .dimension_err(Dimension::NONE, "normal")
+            .and(offset.dimension_err(units::m, "offset"))
+        {
+            panic!("{e}")
+        }
+        self.record(UniverseAction::AddPlane(normal, offset));
+        self.planes.push(Collider::Plane {
+            normal: normal.normalized(),
+            offset,
+        });
+    }
 
-        for (obj_a, obj_b) in possible_collisions {
-            let a = &self.objects[obj_a];
-            let b = &self.objects[obj_b];
-            if let Some(normal) = a.collider().collides(&b.collider()) {
-                let u_a = a.velocity();
-                let u_b = b.velocity();
-                let m_a = a.mass();
-                let m_b = b.mass();
+    pub fn planes(&self) -> &[Collider<N>] {
+        &self.planes
+    }
+
+    /// Connects two objects with a spring of the given stiffness (N/m),
+    /// its rest length is their current distance. Panics if `a` and `b` are the same object
+    #[track_caller]
+    pub fn add_spring(&mut self, a: ObjectID, b: ObjectID, stiffness: Scalar) {
+        if let Err(e) = stiffness.dimension_err(units::N / units::m, "stiffness") {
+            panic!("{e}")
+        }
+        if a == b {
+            panic!("Cannot connect object {a:?} to itself with a spring")
+        }
+        let (a_index, b_index) = (self.index_of(a), self.index_of(b));
+        self.record(UniverseAction::AddSpring(a, b, stiffness));
+        self.springs.push(Spring {
+            a: a_index,
+            b: b_index,
+            rest_length: self.objects[a_index]
+                .position
+                .distance(self.objects[b_index].position),
+            stiffness,
+        });
+    }
+
+    /// Fills `bounds` with a lattice of `resolution` objects sharing `mass`, each connected
+    /// by springs of the given stiffness to every neighbour of its lattice cells,
+    /// which gives both structural and shear springs
+    #[track_caller]
+    pub fn add_soft_body(
+        &mut self,
+        bounds: BoundingBox<N>,
+        resolution: [usize; N],
+        mass: Scalar,
+        stiffness: Scalar,
+    ) -> Vec<ObjectID> {
+        if let Err(e) = bounds
+            .min
+            .dimension_err(units::m, "bounds")
+            .and(mass.dimension_err(units::kg, "mass"))
+        {
+            panic!("{e}")
+        }
+        let count = resolution.iter().product::<usize>();
+        let coords = |mut index: usize| {
+            resolution.map(|n| {
+                let coord = index % n;
+                index /= n;
+                coord
+            })
+        };
+
+        let mut spacing = bounds.max - bounds.min;
+        for (i, &n) in resolution.iter().enumerate() {
+            spacing[i] /= n.saturating_sub(1).max(1) as Float;
+        }
+        let size = (0..N)
+            .filter(|&i| resolution[i] > 1)
+            .map(|i| spacing[i])
+            .fold(Float::INFINITY, Float::min);
+        let size = if size.is_finite() { 0.25 * size } else { 0.0 } * units::m;
 
-                let e = 0.5
-                    * (a.attributes().restitution_coefficient
-                        + b.attributes().restitution_coefficient);
+        let ids = (0..count)
+            .map(|index| {
+                let mut position = bounds.min;
+                for (i, coord) in coords(index).into_iter().enumerate() {
+                    position[i] += coord as Float * spacing[i];
+                }
+                self.add_object(
+                    ObjectBuilder::new_at(position)
+                        .with_mass(mass / count as Float)
+                        .with_size(size)
+                        .build()
+                        .expect("the lattice is built from checked dimensions"),
+                )
+            })
+            .collect::<Vec<_>>();
 
-                let n = normal.normalized();
-                let j = -(1.0 + e) * (u_a - u_b).dot(n) / (m_a.recip() + m_b.recip()) * n;
-                self.objects[obj_a].acc = 2.0 * j / (m_a * h());
-                self.objects[obj_b].acc = -2.0 * j / (m_b * h());
+        for a in 0..count {
+            for b in a + 1..count {
+                let (ca, cb) = (coords(a), coords(b));
+                if (0..N).all(|i| ca[i].abs_diff(cb[i]) <= 1) {
+                    self.add_spring(ids[a], ids[b], stiffness);
+                }
             }
         }
+        ids
     }
-}
 
-impl<const N: usize> Default for Universe<N> {
-    fn default() -> Self {
-        Self::new()
+    /// Drops the IDs, springs, tracking and contacts of removed objects and renumbers the
+    /// rest, `keep` flags the objects that stay. Sleeping objects touching a removed one or
+    /// connected to it by a spring wake up, as they may have lost their support.
+    /// Called before the objects themselves are removed
+    fn retain_references(&mut self, keep: &[bool]) {
+        let removed = (0..keep.len()).filter(|&i| !keep[i]).collect::<Vec<_>>();
+        // Resting contacts hover a hair apart, so touching allows a gap of 1% of the sizes
+        let touches = |a: &Object<N>, b: &Object<N>| {
+            let reach = (a.size() + b.size()) * 1.01;
+            a.position.distance(b.position) <= reach
+        };
+        let neighbours = (0..keep.len())
+            .filter(|&i| keep[i] && self.objects[i].asleep)
+            .filter(|&i| {
+                removed
+                    .iter()
+                    .any(|&j| touches(&self.objects[i], &self.objects[j]))
+                    || self.springs.iter().any(|spring| {
+                        spring.a == i && !keep[spring.b] || spring.b == i && !keep[spring.a]
+                    })
+            })
+            .collect::<Vec<_>>();
+        for i in neighbours {
+            self.objects[i].wake();
+        }
+
+        let mut index = Vec::with_capacity(keep.len());
+        let mut next = 0;
+        for &kept in keep {
+            index.push(next);
+            next += kept as usize;
+        }
+        self.springs.retain_mut(|spring| {
+            let kept = keep[spring.a] && keep[spring.b];
+            spring.a = index[spring.a];
+            spring.b = index[spring.b];
+            kept
+        });
+        let ids = std::mem::take(&mut self.ids);
+        self.tracked.retain(|&(id, _)| {
+            let slot = self.slots[id.slot].1;
+            slot.is_some_and(|i| keep[i])
+        });
+        for (i, id) in ids.into_iter().enumerate() {
+            if keep[i] {
+                self.slots[id.slot].1 = Some(index[i]);
+                self.ids.push(id);
+            } else {
+                self.free_slot(id);
+            }
+        }
+        self.touching = self
+            .touching
+            .iter()
+            .filter_map(|&touching| match touching {
+                Touching::Objects(a, b) if keep[a] && keep[b] => {
+                    Some(Touching::Objects(index[a], index[b]))
+                }
+                Touching::Plane(i, p) if keep[i] => Some(Touching::Plane(index[i], p)),
+                _ => None,
+            })
+            .collect();
     }
-}
 
-impl<const N: usize, const T: usize> From<[Object<N>; T]> for Universe<N> {
-    fn from(objects: [Object<N>; T]) -> Self {
-        let mut world = Self::new();
-        world.with_objects(objects);
-        world
+    pub fn with_objects(&mut self, objects: impl IntoIterator<Item = Object<N>>) -> &mut Self {
+        for object in objects {
+            self.add_object(object);
+        }
+        self
+    }
+
+    pub fn remove_objects<F>(&mut self, f: F)
+    where
+        F: FnMut(&Object<N>) -> bool,
+    {
+        let keep = self.objects.iter().map(f).collect::<Vec<_>>();
+        self.retain_flagged(&keep);
+        self.record(UniverseAction::RetainObjects(keep));
+    }
+
+    /// Keeps the objects for which `f` returns true, the kept objects keep their IDs
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(ObjectID, &Object<N>) -> bool,
+    {
+        let keep = self
+            .objects
+            .iter()
+            .enumerate()
+            .map(|(i, object)| f(self.ids[i], object))
+            .collect::<Vec<_>>();
+        self.retain_flagged(&keep);
+        self.record(UniverseAction::RetainObjects(keep));
+    }
+
+    /// Keeps the objects flagged in `keep` along with their springs
+    fn retain_flagged(&mut self, keep: &[bool]) {
+        self.retain_references(keep);
+        let mut flags = keep.iter();
+        self.objects.retain(|_| *flags.next().unwrap());
+    }
+
+    /// Makes `step` do nothing until `resume` is called, `step_once` still advances
+    pub fn pause(&mut self) {
+        self.record(UniverseAction::Paused(true));
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.record(UniverseAction::Paused(false));
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Records the time and position of `object` after every step, keeping the latest
+    /// samples up to the trajectory capacity. Tracking an object twice does nothing.
+    /// Panics if there is no such object
+    #[track_caller]
+    pub fn track(&mut self, object: ObjectID) {
+        self.index_of(object);
+        self.record(UniverseAction::Track(object));
+        if !self.tracked.iter().any(|&(id, _)| id == object) {
+            self.tracked.push((object, Vec::new()));
+        }
+    }
+
+    /// Samples of a tracked object, oldest first, empty if it isn't tracked
+    pub fn tracked_trajectory(&self, object: ObjectID) -> &[(Scalar, Vector<N>)] {
+        match self.tracked.iter().find(|&&(id, _)| id == object) {
+            Some((_, samples)) => {
+                &samples[samples.len().saturating_sub(self.trajectory_capacity)..]
+            }
+            None => &[],
+        }
+    }
+
+    /// How many samples are kept per tracked object (at least 1, default 1000)
+    pub fn set_trajectory_capacity(&mut self, capacity: usize) {
+        self.record(UniverseAction::TrajectoryCapacity(capacity));
+        self.trajectory_capacity = capacity.max(1);
+    }
+
+    /// Appends the tracked positions. Samples pile up to twice the capacity
+    /// before the oldest are dropped at once, so recording stays O(1) amortized
+    fn record_tracked(&mut self) {
+        let time = self.time();
+        for (id, samples) in &mut self.tracked {
+            if samples.len() >= 2 * self.trajectory_capacity {
+                samples.drain(..samples.len() - self.trajectory_capacity + 1);
+            }
+            let index = self.slots[id.slot]
+                .1
+                .expect("removed objects are untracked");
+            samples.push((time, self.objects[index].position));
+        }
+    }
+
+    /// Advances the simulation by exactly one substep of `STEP`, even while paused
+    pub fn step_once(&mut self) {
+        self.record(UniverseAction::StepOnce);
+        self.advance(None, 1, h());
+    }
+
+    /// Advances the simulation by `dt` seconds, see [`Universe::step_dt`]
+    pub fn step(&mut self, dt: Float) {
+        self.step_dt(dt * units::s)
+            .expect("a Float * s is always a time");
+    }
+
+    /// Advances the simulation by the time `dt` in substeps of `STEP`,
+    /// the leftover time is carried over to the next call.
+    /// With an [`AdaptiveStep`] all of `dt` is covered in substeps of varying length.
+    /// Does nothing while paused
+    pub fn step_dt(&mut self, dt: Scalar) -> Result<(), DimensionError> {
+        dt.dimension_err(units::s, "dt")?;
+        if self.paused {
+            return Ok(());
+        }
+        self.record(UniverseAction::Step(dt.value()));
+        let substeps = match self.adaptive_step {
+            None => {
+                self.accumulator += dt.value();
+                let substeps = (self.accumulator / STEP) as usize;
+                self.accumulator = (self.accumulator - substeps as Float * STEP).max(0.0);
+                substeps
+            }
+            Some(_) => (dt > 0.0) as usize,
+        };
+        if substeps != 0 {
+            self.advance(self.adaptive_step, substeps, dt);
+        }
+        Ok(())
+    }
+
+    /// Runs `substeps` substeps of `STEP`, or adaptive substeps covering `dt`
+    fn advance(&mut self, adaptive_step: Option<AdaptiveStep>, substeps: usize, dt: Scalar) {
+        let (x_frame, v_frame) = match self.frame {
+            Frame::World => (
+                Vector::zero() * units::m,
+                Vector::zero() * units::m / units::s,
+            ),
+            Frame::CenterOfMomentum => (self.center_of_mass(), self.center_of_mass_velocity()),
+        };
+        for object in self.objects.iter_mut() {
+            object.position -= x_frame;
+            object.velocity -= v_frame;
+        }
+
+        let (elapsed, last) = match adaptive_step {
+            None => {
+                for step in 0..substeps {
+                    self.substep(h(), x_frame + v_frame * (step as Float * h()), v_frame);
+                }
+                (substeps as Float * h(), h())
+            }
+            Some(adaptive) => {
+                let (mut elapsed, mut last) = (0.0 * units::s, h());
+                while elapsed < dt {
+                    last = self.adaptive_substep(adaptive);
+                    if last > dt - elapsed {
+                        last = dt - elapsed;
+                    }
+                    self.substep(last, x_frame + v_frame * elapsed, v_frame);
+                    elapsed += last;
+                }
+                (elapsed, last)
+            }
+        };
+
+        for object in self.objects.iter_mut() {
+            object.position += x_frame + v_frame * elapsed;
+            object.previous_position += x_frame + v_frame * (elapsed - last);
+            object.velocity += v_frame;
+        }
+        self.record_tracked();
+    }
+
+    /// Length of the next substep from the accelerations of the previous one
+    fn adaptive_substep(&self, adaptive: AdaptiveStep) -> Scalar {
+        let a_max = self
+            .objects
+            .iter()
+            .map(|object| object.acc.magnitude().value())
+            .fold(0.0, Float::max);
+        let dt = (adaptive.softening.value() / a_max).sqrt();
+        dt.clamp(adaptive.min.value(), adaptive.max.value()) * units::s
+    }
+
+    /// `x_frame` is the origin of the frame the objects are stored in at the start of the
+    /// substep and `v_frame` its velocity
+    fn substep(&mut self, dt: Scalar, x_frame: Vector<N>, v_frame: Vector<N>) {
+        self.substeps += 1;
+        self.time += dt.value() as f64;
+        let subdivided = self.fast_subdivisions > 1;
+        let mut half_kicked = Vec::with_capacity(self.objects.len());
+        for object in self.objects.iter_mut() {
+            object.previous_position = object.position;
+            if object.asleep {
+                half_kicked.push(object.velocity);
+                continue;
+            }
+            let v = object.velocity + 0.5 * dt * object.acc;
+            if !(subdivided && object.attributes().fast) {
+                object.position += v * dt;
+            }
+            half_kicked.push(v);
+        }
+        if subdivided && self.objects.iter().any(|o| o.attributes().fast) {
+            self.subdivide_fast(dt, x_frame, v_frame);
+        }
+        // Where the frame's origin is once the objects have drifted
+        let x_end = x_frame + v_frame * dt;
+
+        let pair_forces = Self::pair_forces(&self.objects, self.compensated_summation);
+        let spring_forces = Self::spring_forces(&self.objects, &self.springs);
+        for (i, object) in self.objects.iter_mut().enumerate() {
+            if !(object.asleep || subdivided && object.attributes().fast) {
+                let v = half_kicked[i];
+                let force = pair_forces[i]
+                    + Self::force(object, v_frame, self.field_g, self.field_E, self.field_B)
+                    + self.uniform_force
+                    + spring_forces[i]
+                    + Self::fictitious_force(
+                        self.rotation,
+                        object.mass(),
+                        object.position + x_end,
+                        v + v_frame,
+                    );
+                object.acc = object.acceleration(force);
+
+                object.velocity = v + object.acc * dt * 0.5;
+            }
+            // The limit applies to the speed in the world, not in the frame
+            if let Some(limit) = self.speed_limit.filter(|_| !object.is_massless()) {
+                object.velocity = (object.velocity + v_frame)
+                    .clamp_magnitude(limit)
+                    .expect("the speed limit is checked to be a speed")
+                    - v_frame;
+            }
+            // Torques only come as contact impulses, in between the angular momentum is
+            // constant. Euler's equations for an asymmetric object then come down to
+            // keeping `L = R I Rᵀ ω` fixed while `R` turns
+            if object.is_spherical_top() {
+                object.orientation = object.orientation.integrate(object.angular_velocity, dt);
+            } else {
+                let l = object.angular_momentum();
+                object.orientation = object.orientation.integrate(object.angular_velocity, dt);
+                object.angular_velocity = object.angular_velocity_from(l);
+            }
+
+            if let Some(lifetime) = &mut object.attributes_mut().lifetime {
+                *lifetime -= dt;
+            }
+        }
+        self.resolve_collisions(x_end, v_frame);
+        if let Some((threshold, time)) = self.sleeping {
+            self.fall_asleep(threshold, time, dt);
+        }
+
+        let expired = |object: &Object<N>| object.attributes().lifetime.is_some_and(|t| t <= 0.0);
+        if self.objects.iter().any(expired) {
+            let keep = self.objects.iter().map(|o| !expired(o)).collect::<Vec<_>>();
+            self.retain_flagged(&keep);
+        }
+    }
+
+    /// Advances the fast objects across the substep in `fast_subdivisions` velocity Verlet
+    /// steps, with the other objects, already drifted, moving linearly from their previous
+    /// positions to their new ones in the meantime
+    fn subdivide_fast(&mut self, dt: Scalar, x_frame: Vector<N>, v_frame: Vector<N>) {
+        let subdivisions = self.fast_subdivisions;
+        let h = dt / subdivisions as Float;
+        let is_fast = |object: &Object<N>| object.attributes().fast && !object.asleep;
+        let fast = (0..self.objects.len())
+            .filter(|&i| is_fast(&self.objects[i]))
+            .collect::<Vec<_>>();
+        let ends = self.objects.iter().map(|o| o.position).collect::<Vec<_>>();
+        for step in 1..=subdivisions {
+            let alpha = step as Float / subdivisions as Float;
+            let mut half_kicked = Vec::with_capacity(fast.len());
+            for (object, &end) in self.objects.iter_mut().zip(&ends) {
+                if is_fast(object) {
+                    let v = object.velocity + 0.5 * h * object.acc;
+                    object.position += v * h;
+                    half_kicked.push(v);
+                } else {
+                    object.position = object.previous_position * (1.0 - alpha) + end * alpha;
+                }
+            }
+
+            let spring_forces = Self::spring_forces(&self.objects, &self.springs);
+            let x_frame = x_frame + v_frame * (h * step as Float);
+            for (&i, v) in fast.iter().zip(half_kicked) {
+                let object = &self.objects[i];
+                let force = Self::pair_forces_on(&self.objects, i, self.compensated_summation)
+                    + Self::force(object, v_frame, self.field_g, self.field_E, self.field_B)
+                    + self.uniform_force
+                    + spring_forces[i]
+                    + Self::fictitious_force(
+                        self.rotation,
+                        object.mass(),
+                        object.position + x_frame,
+                        v + v_frame,
+                    );
+                let object = &mut self.objects[i];
+                object.acc = object.acceleration(force);
+                object.velocity = v + object.acc * h * 0.5;
+            }
+        }
+    }
+
+    /// Counts how long each awake object has been slower than `threshold`
+    /// and stops those that have been for `time`
+    fn fall_asleep(&mut self, threshold: Scalar, time: Scalar, dt: Scalar) {
+        for object in self.objects.iter_mut() {
+            if object.asleep || object.is_massless() {
+                continue;
+            }
+            if object.velocity.magnitude() >= threshold {
+                object.idle = 0.0 * units::s;
+                continue;
+            }
+            object.idle += dt;
+            if object.idle >= time {
+                object.asleep = true;
+                object.velocity = Vector::zero() * object.velocity.dim();
+                object.angular_velocity = Vector::zero() * object.angular_velocity.dim();
+            }
+        }
+    }
+
+    /// Sleeping objects don't move in contacts, as if their mass were infinite
+    fn contact_mass(object: &Object<N>) -> Scalar {
+        if object.asleep {
+            Float::INFINITY * units::kg
+        } else {
+            object.mass()
+        }
+    }
+
+    /// `r²/I` for a point on the surface, zero for objects without a moment of inertia.
+    /// Friction along a surface of normal `normal` turns objects about the axes of the
+    /// surface in 3D, `I` is the moment averaged over them, and about z in 2D
+    fn inverse_inertia(object: &Object<N>, normal: Vector<N>) -> Scalar {
+        if !Self::has_inertia(object) {
+            return 0.0 / units::kg;
+        }
+        let about = |axis: Vector<3>| axis.dot(object.angular_velocity_from(axis));
+        let inverse = if N <= 2 {
+            about(Vector::basis(2))
+        } else {
+            let trace = about(Vector::basis(0)) + about(Vector::basis(1)) + about(Vector::basis(2));
+            (trace - about(embed(normal.normalized()))) * 0.5
+        };
+        object.size().squared() * inverse
+    }
+
+    /// Massless objects have no moment of inertia, contacts can't spin them
+    fn has_inertia(object: &Object<N>) -> bool {
+        object.moment_of_inertia().0.iter().all(|&i| i > 0.0)
+    }
+
+    /// Offsets of the touching points of a contact from the centers of its objects
+    fn lever_arms(objects: &[Object<N>], contact: &Contact<N>) -> (Vector<N>, Vector<N>) {
+        (
+            -objects[contact.a].size() * contact.normal,
+            objects[contact.b].size() * contact.normal,
+        )
+    }
+
+    /// Velocity of the point of `object` at `r` from its center
+    fn point_velocity(object: &Object<N>, r: Vector<N>) -> Vector<N> {
+        object.velocity + flatten(object.angular_velocity.cross(embed(r)))
+    }
+
+    /// Turns the angular impulse `l` of a contact into spin, sleeping objects don't move
+    fn spin(object: &mut Object<N>, l: Vector<3>) {
+        if !object.asleep && Self::has_inertia(object) {
+            object.angular_velocity += object.angular_velocity_from(l);
+        }
+    }
+
+    /// Fraction of a substep left over in the accumulator after the last `step`
+    pub fn interpolation_alpha(&self) -> Float {
+        self.accumulator / STEP
+    }
+
+    /// Positions blended between the last two substeps,
+    /// `alpha = 0` gives the previous positions and `alpha = 1` the current ones
+    pub fn interpolated_positions(&self, alpha: Float) -> Vec<Vector<N>> {
+        self.objects
+            .iter()
+            .map(|object| object.previous_position * (1.0 - alpha) + object.position * alpha)
+            .collect()
+    }
+
+    /// Centrifugal and Coriolis forces in a frame rotating at `ω` around the origin, on an
+    /// object at `position` moving at `velocity` in the world
+    fn fictitious_force(
+        ω: Vector<3>,
+        mass: Scalar,
+        position: Vector<N>,
+        velocity: Vector<N>,
+    ) -> Vector<N> {
+        if ω.is_zero() {
+            return Vector::zero() * units::N;
+        }
+        let (r, v) = (embed(position), embed(velocity));
+        flatten(-mass * (ω.cross(ω.cross(r)) + 2.0 * ω.cross(v)))
+    }
+
+    /// Gravity and Coulomb forces between every pair of objects. Each pair is evaluated once
+    /// and the opposite force applied to the other object, by Newton's third law.
+    /// Every object receives its forces in the order of the other objects' IDs
+    fn pair_forces(objects: &[Object<N>], compensated: bool) -> Vec<Vector<N>> {
+        let zero = Vector::zero() * units::N;
+        let empty = if compensated {
+            CompensatedSum::new(zero)
+        } else {
+            CompensatedSum::naive(zero)
+        };
+        let mut forces = vec![empty; objects.len()];
+        for i in 0..objects.len() {
+            for j in i + 1..objects.len() {
+                let force = Self::pair_force(objects, i, j);
+                forces[i].add(force);
+                forces[j].add(-force);
+            }
+        }
+        forces.iter().map(CompensatedSum::total).collect()
+    }
+
+    /// Gravity and Coulomb forces of the object at `j` on the one at `i`. Always evaluated
+    /// from the lower index, so the two objects of a pair get exactly opposite forces
+    fn pair_force(objects: &[Object<N>], i: usize, j: usize) -> Vector<N> {
+        let force = |a, b| gravitational_force(a, b) + coulomb_force(a, b);
+        if i < j {
+            force(&objects[i], &objects[j])
+        } else {
+            -force(&objects[j], &objects[i])
+        }
+    }
+
+    /// Gravity and Coulomb forces of every other object on the object at `i`
+    fn pair_forces_on(objects: &[Object<N>], i: usize, compensated: bool) -> Vector<N> {
+        let zero = Vector::zero() * units::N;
+        let mut force = if compensated {
+            CompensatedSum::new(zero)
+        } else {
+            CompensatedSum::naive(zero)
+        };
+        force.extend(
+            (0..objects.len())
+                .filter(|&j| j != i)
+                .map(|j| Self::pair_force(objects, i, j)),
+        );
+        force.total()
+    }
+
+    /// Forces of the universe's fields
+    fn force(
+        object: &Object<N>,
+        v_frame: Vector<N>,
+        g: Vector<N>,
+        E: Vector<N>,
+        B: Vector<N>,
+    ) -> Vector<N> {
+        let mut force = object.charge() * E + object.mass() * g * object.attributes().gravity_scale;
+        if N == 3 {
+            let velocity = object.velocity + v_frame;
+            let vB = (velocity[1] * B[2] - velocity[2] * B[1]) * Vector::basis(0)
+                - (velocity[0] * B[2] - velocity[2] * B[0]) * Vector::basis(1)
+                + (velocity[0] * B[1] - velocity[1] * B[0]) * Vector::basis(2);
+            force += object.charge() * vB * units::N / units::C;
+        } else if !B.is_zero() {
+            panic!("B field in non 3D space");
+        }
+        force
+    }
+
+    /// Force of the springs on every object, in a single pass over the springs.
+    /// Springs whose ends coincide pull in no defined direction and are skipped
+    fn spring_forces(f: &[Object<N>], springs: &[Spring]) -> Vec<Vector<N>> {
+        let mut forces = vec![Vector::zero() * units::N; f.len()];
+        for spring in springs {
+            let r = f[spring.b].position - f[spring.a].position;
+            if r.is_zero() {
+                continue;
+            }
+            let force = r.normalized() * spring.stiffness * (r.magnitude() - spring.rest_length);
+            forces[spring.a] += force;
+            forces[spring.b] -= force;
+        }
+        forces
+    }
+
+    /// `x_frame` and `v_frame` place the frame the objects are stored in, the planes are
+    /// at rest in the world
+    fn resolve_collisions(&mut self, x_frame: Vector<N>, v_frame: Vector<N>) {
+        let mut touching = HashSet::new();
+        let mut persisting = Vec::new();
+        let mut contacts = Vec::new();
+        let mut elastic = true;
+        let mut broken = Vec::new();
+        let mut woken = Vec::new();
+        for (obj_a, obj_b) in self.broad_phase() {
+            let a = &self.objects[obj_a];
+            let b = &self.objects[obj_b];
+            if a.is_massless() || b.is_massless() || a.attributes().shares_group(&b.attributes()) {
+                continue;
+            }
+            if let Some(normal) = a.collider().collides(&b.collider()) {
+                if let Some((threshold, _)) = self.sleeping {
+                    if a.asleep && b.velocity.magnitude() >= threshold {
+                        woken.push(obj_a);
+                    }
+                    if b.asleep && a.velocity.magnitude() >= threshold {
+                        woken.push(obj_b);
+                    }
+                }
+                if let Some((threshold, pieces)) = self.fragmentation {
+                    let reduced_mass = (a.mass().recip() + b.mass().recip()).recip();
+                    let approach = (a.velocity - b.velocity).dot(normal.normalized());
+                    let energy = 0.5 * reduced_mass * approach.squared();
+                    if pieces > 1 && approach < 0.0 && energy > threshold {
+                        let struck = if a.mass() < b.mass() { obj_a } else { obj_b };
+                        broken.push((struck, energy - threshold));
+                    }
+                }
+                let e = a.attributes().combined_restitution(&b.attributes());
+                elastic &= e <= 1.0;
+                // A contact that persists from the last substep has already bounced
+                let key = Touching::Objects(obj_a.min(obj_b), obj_a.max(obj_b));
+                let e = if self.touching.contains(&key) {
+                    persisting.push((obj_a, obj_b, normal));
+                    0.0
+                } else {
+                    e
+                };
+                touching.insert(key);
+                let mass = |i, object: &Object<N>| match woken.contains(&i) {
+                    true => object.mass(),
+                    false => Self::contact_mass(object),
+                };
+                let inverse_inertia = |i, object: &Object<N>| match woken.contains(&i) {
+                    false if object.asleep => 0.0 / units::kg,
+                    _ => Self::inverse_inertia(object, normal),
+                };
+                contacts.push((
+                    normal.magnitude(),
+                    Contact::new(
+                        (obj_a, a.velocity(), mass(obj_a, a)),
+                        (obj_b, b.velocity(), mass(obj_b, b)),
+                        normal,
+                        e,
+                        a.attributes().combined_friction(&b.attributes()),
+                    )
+                    .with_spin(inverse_inertia(obj_a, a) + inverse_inertia(obj_b, b)),
+                ));
+            }
+        }
+
+        for &i in &woken {
+            self.objects[i].wake();
+        }
+
+        // Planes are unbounded so they skip the broad phase, objects in them are pushed
+        // back onto the surface since the plane cannot move. In the objects' frame
+        // the planes move at -v_frame
+        let mut plane_contacts = Vec::new();
+        let mut rolling = Vec::new();
+        let plane_velocity = -v_frame;
+        for (i, object) in self.objects.iter_mut().enumerate() {
+            if object.is_massless() || object.asleep {
+                continue;
+            }
+            for (p, plane) in self.planes.iter().enumerate() {
+                if let Some(penetration) = object.collider().translated(x_frame).collides(plane) {
+                    object.position += penetration;
+                    let key = Touching::Plane(i, p);
+                    let e = if self.touching.contains(&key) {
+                        0.0
+                    } else {
+                        object.attributes().restitution_coefficient
+                    };
+                    touching.insert(key);
+                    plane_contacts.push((
+                        penetration.magnitude(),
+                        Contact::new(
+                            (i, object.velocity(), object.mass()),
+                            (p, plane_velocity, Float::INFINITY * units::kg),
+                            penetration,
+                            e,
+                            object.attributes().friction_coefficient,
+                        )
+                        .with_spin(Self::inverse_inertia(object, penetration)),
+                    ));
+                    elastic &= object.attributes().restitution_coefficient <= 1.0;
+                    if object.attributes().rolling && N > 1 {
+                        rolling.push((i, penetration.normalized()));
+                    }
+                }
+            }
+        }
+
+        self.touching = touching;
+
+        let [mut contacts, mut plane_contacts] = [contacts, plane_contacts].map(|mut contacts| {
+            if self.deepest_contacts_first {
+                contacts.sort_by(|(a, _), (b, _)| b.value().total_cmp(&a.value()));
+            }
+            contacts
+                .into_iter()
+                .map(|(_, contact)| contact)
+                .collect::<Vec<_>>()
+        });
+
+        let before = (self.verify_collisions
+            && !(contacts.is_empty() && plane_contacts.is_empty()))
+        .then(|| {
+            self.objects
+                .iter()
+                .map(|o| (o.velocity, o.angular_velocity))
+                .collect::<Vec<_>>()
+        });
+
+        for _ in 0..self.solver_iterations {
+            match self.contact_solver {
+                ContactSolver::Sequential => {
+                    for contact in contacts.iter_mut() {
+                        let (a, b) = (contact.a, contact.b);
+                        let (r_a, r_b) = Self::lever_arms(&self.objects, contact);
+                        let (j_n, j_t) = contact.solve(
+                            Self::point_velocity(&self.objects[a], r_a),
+                            Self::point_velocity(&self.objects[b], r_b),
+                        );
+                        let (m_a, m_b) = (
+                            Self::contact_mass(&self.objects[a]),
+                            Self::contact_mass(&self.objects[b]),
+                        );
+                        self.objects[a].velocity += (j_n + j_t) / m_a;
+                        self.objects[b].velocity -= (j_n + j_t) / m_b;
+                        Self::spin(&mut self.objects[a], embed(r_a).cross(embed(j_t)));
+                        Self::spin(&mut self.objects[b], embed(r_b).cross(embed(-j_t)));
+                    }
+                    for contact in plane_contacts.iter_mut() {
+                        let object = &mut self.objects[contact.a];
+                        let r = -object.size() * contact.normal;
+                        let (j_n, j_t) =
+                            contact.solve(Self::point_velocity(object, r), plane_velocity);
+                        object.velocity += (j_n + j_t) / object.mass();
+                        Self::spin(object, embed(r).cross(embed(j_t)));
+                    }
+                }
+                ContactSolver::Simultaneous => {
+                    let objects = &self.objects;
+                    let momentum = Vector::zero() * units::kg * plane_velocity.dim();
+                    let mut impulses = vec![momentum; objects.len()];
+                    let mut angular_impulses = vec![embed(momentum) * units::m; objects.len()];
+                    for contact in contacts.iter_mut() {
+                        let (a, b) = (contact.a, contact.b);
+                        let (r_a, r_b) = Self::lever_arms(objects, contact);
+                        let (j_n, j_t) = contact.solve(
+                            Self::point_velocity(&objects[a], r_a),
+                            Self::point_velocity(&objects[b], r_b),
+                        );
+                        impulses[a] += j_n + j_t;
+                        impulses[b] -= j_n + j_t;
+                        angular_impulses[a] += embed(r_a).cross(embed(j_t));
+                        angular_impulses[b] += embed(r_b).cross(embed(-j_t));
+                    }
+                    for contact in plane_contacts.iter_mut() {
+                        let a = contact.a;
+                        let r = -objects[a].size() * contact.normal;
+                        let (j_n, j_t) =
+                            contact.solve(Self::point_velocity(&objects[a], r), plane_velocity);
+                        impulses[a] += j_n + j_t;
+                        angular_impulses[a] += embed(r).cross(embed(j_t));
+                    }
+                    for ((object, j), l) in
+                        self.objects.iter_mut().zip(impulses).zip(angular_impulses)
+                    {
+                        if !j.is_zero() {
+                            object.velocity += j / Self::contact_mass(object);
+                            Self::spin(object, l);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Contacts that persist are pushed apart instead, like objects in planes
+        for (a, b, penetration) in persisting {
+            let inv_mass = [a, b].map(|i| Self::contact_mass(&self.objects[i]).recip());
+            let total = inv_mass[0] + inv_mass[1];
+            self.objects[a].position += penetration * (inv_mass[0] / total);
+            self.objects[b].position -= penetration * (inv_mass[1] / total);
+        }
+
+        if let Some(before) = before {
+            let mut bodies = contacts
+                .iter()
+                .flat_map(|contact| [contact.a, contact.b])
+                .chain(plane_contacts.iter().map(|contact| contact.a))
+                .collect::<Vec<_>>();
+            bodies.sort_unstable();
+            bodies.dedup();
+            // Planes and sleeping objects absorb momentum
+            let movable =
+                plane_contacts.is_empty() && !bodies.iter().any(|&i| self.objects[i].asleep);
+            self.verify_conservation(&before, &bodies, movable, elastic);
+        }
+
+        self.roll(&rolling, v_frame);
+
+        if !broken.is_empty() {
+            self.fragment(broken);
+        }
+    }
+
+    /// Gives each object touching a plane along `normal` the tangential impulse that stops
+    /// its contact point from sliding, trading speed for spin. The moment of inertia is
+    /// written `k m r²` about the axes of the plane, `v_frame` is the velocity of the objects' frame.
+    /// The impulse isn't capped by the friction coefficient, see [`ObjectAttributes::rolling`]
+    fn roll(&mut self, rolling: &[(usize, Vector<N>)], v_frame: Vector<N>) {
+        for &(i, normal) in rolling {
+            let object = &mut self.objects[i];
+            let (mass, size) = (object.mass(), object.size());
+            let k = (mass * Self::inverse_inertia(object, normal))
+                .recip()
+                .value();
+            let r = embed(-size * normal);
+            let n = embed(normal);
+            let u = embed(object.velocity + v_frame) + object.angular_velocity.cross(r);
+            let j = -(u - u.dot(n) * n) * (mass * k / (1.0 + k));
+            object.velocity += flatten(j) / mass;
+            object.angular_velocity += object.angular_velocity_from(r.cross(j));
+        }
+    }
+
+    /// Replaces each object in `broken` by fragments sharing the given excess energy
+    fn fragment(&mut self, mut broken: Vec<(usize, Scalar)>) {
+        let Some((_, pieces)) = self.fragmentation else {
+            return;
+        };
+        broken.sort_unstable_by_key(|&(i, _)| std::cmp::Reverse(i));
+        broken.dedup_by_key(|&mut (i, _)| i);
+
+        for (i, energy) in broken {
+            self.retain_references(&(0..self.objects.len()).map(|j| j != i).collect::<Vec<_>>());
+            let object = self.objects.remove(i);
+            let mass = object.mass() / pieces as Float;
+
+            let mut directions = (0..pieces)
+                .map(|_| Vector(std::array::from_fn(|_| self.next_random()), Dimension::NONE))
+                .collect::<Vec<Vector<N>>>();
+            let mean = directions.iter().fold(Vector::zero(), |acc, &d| acc + d) / pieces as Float;
+            directions.iter_mut().for_each(|d| *d -= mean);
+            let spread = directions
+                .iter()
+                .fold(0.0, |acc, d| acc + d.squared().value());
+            let speed = if spread > 0.0 {
+                (2.0 * energy / (mass * spread)).sqrt()
+            } else {
+                Scalar::ZERO * units::m / units::s
+            };
+
+            // Objects stand for 3D balls in every dimension, as with materials, so the volume
+            // shared by the fragments is that of a ball. They start along their directions,
+            // far enough out not to overlap each other
+            let size = object.size() * (pieces as Float).powf(-1.0 / 3.0);
+            let outward = directions
+                .iter()
+                .map(|&d| if d.is_zero() { d } else { d.normalized() })
+                .collect::<Vec<_>>();
+            let closest = outward
+                .iter()
+                .enumerate()
+                .flat_map(|(k, &a)| outward[k + 1..].iter().map(move |&b| a.distance(b).value()))
+                .filter(|&distance| distance > 0.0)
+                .fold(Float::INFINITY, Float::min);
+            let spacing = size * (2.0 / closest);
+            let radius = if spacing > object.size() - size {
+                spacing
+            } else {
+                object.size() - size
+            };
+            let center = outward.iter().fold(Vector::zero(), |acc, &u| acc + u) / pieces as Float;
+
+            for (direction, u) in directions.into_iter().zip(outward) {
+                let fragment = ObjectBuilder::new_at(object.position + (u - center) * radius)
+                    .with_velocity(object.velocity + direction * speed)
+                    .with_mass(mass)
+                    .with_charge(object.charge() / pieces as Float)
+                    .with_size(size)
+                    .with_color(object.color())
+                    .with_attributes(object.attributes())
+                    .with_orientation(object.orientation())
+                    .with_angular_velocity(object.angular_velocity())
+                    .build()
+                    .expect("fragments have the dimensions of the original object");
+                self.push_object(fragment);
+            }
+        }
+    }
+
+    /// Uniform in [-1, 1)
+    fn next_random(&mut self) -> Float {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 40) as Float / (1u64 << 23) as Float - 1.0
+    }
+
+    /// Compares the kinetic energy and momentum of `bodies` with their velocities `before`
+    fn verify_conservation(
+        &mut self,
+        before: &[(Vector<N>, Vector<3>)],
+        bodies: &[usize],
+        check_momentum: bool,
+        check_energy: bool,
+    ) {
+        let mut energy = (Scalar::ZERO * units::J, Scalar::ZERO * units::J);
+        let zero_momentum = Vector::zero() * units::kg * units::m / units::s;
+        let mut momentum = (zero_momentum, zero_momentum);
+        let mut scale = Scalar::ZERO * units::kg * units::m / units::s;
+        for &i in bodies {
+            let object = &self.objects[i];
+            let mass = object.mass();
+            let (v_before, ω_before) = before[i];
+            // Contacts leave the orientation alone, the same `R I Rᵀ` holds before and after
+            energy.0 += 0.5 * mass * v_before.squared()
+                + 0.5 * ω_before.dot(object.angular_momentum_at(ω_before));
+            energy.1 += 0.5 * mass * object.velocity.squared()
+                + 0.5 * object.angular_velocity.dot(object.angular_momentum());
+            momentum.0 += mass * v_before;
+            momentum.1 += mass * object.velocity;
+            scale += mass * v_before.magnitude();
+        }
+
+        let time = self.time();
+        if check_energy && energy.1 > energy.0 * (1.0 + 1e-4) {
+            self.collision_errors
+                .push(CollisionVerificationError::EnergyIncrease {
+                    time,
+                    before: energy.0,
+                    after: energy.1,
+                });
+        }
+        let change = (momentum.1 - momentum.0).magnitude();
+        if check_momentum && change > scale * 1e-4 {
+            self.collision_errors
+                .push(CollisionVerificationError::MomentumChange { time, change });
+        }
+    }
+}
+
+/// The first components of `v` in 3D, angular velocities are always 3D
+pub(crate) fn embed<const N: usize>(v: Vector<N>) -> Vector<3> {
+    let mut components = [0.0; 3];
+    for (x, &y) in components.iter_mut().zip(v.0.iter()) {
+        *x = y;
+    }
+    Vector(components, v.dim())
+}
+
+/// The first `N` components of `v`, the inverse of [`embed`]
+pub(crate) fn flatten<const N: usize>(v: Vector<3>) -> Vector<N> {
+    let mut components = [0.0; N];
+    for (x, &y) in components.iter_mut().zip(v.0.iter()) {
+        *x = y;
+    }
+    Vector(components, v.dim())
+}
+
+impl<const N: usize> Default for Universe<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Classic gravitational configurations with known solutions, for checking integrators.
+/// The bodies are small enough not to touch
+impl Universe<2> {
+    /// Two bodies `separation` apart on circular orbits around their center of mass at the origin,
+    /// with period `2π √(d³ / G(m₁ + m₂))`
+    pub fn two_body_circular(
+        m1: Scalar,
+        m2: Scalar,
+        separation: Scalar,
+    ) -> Result<Universe<2>, DimensionError> {
+        separation.dimension_err(units::m, "separation")?;
+        let v_rel = (crate::constants::G * (m1 + m2) / separation).sqrt();
+        Self::two_body(m1, m2, separation, v_rel)
+    }
+
+    /// Two bodies released at pericenter of a Kepler ellipse with semi-major axis `a`
+    /// and eccentricity `e`, with period `2π √(a³ / G(m₁ + m₂))`
+    pub fn kepler_ellipse(
+        m1: Scalar,
+        m2: Scalar,
+        a: Scalar,
+        e: Float,
+    ) -> Result<Universe<2>, DimensionError> {
+        a.dimension_err(units::m, "a")?;
+        let r_p = a * (1.0 - e);
+        let v_rel = (crate::constants::G * (m1 + m2) * (1.0 + e) / r_p).sqrt();
+        Self::two_body(m1, m2, r_p, v_rel)
+    }
+
+    /// Three equal masses chasing each other around a figure eight (Chenciner & Montgomery),
+    /// with period `6.325914 √(L³ / Gm)` where `L` is `scale`
+    pub fn figure_eight(mass: Scalar, scale: Scalar) -> Result<Universe<2>, DimensionError> {
+        mass.dimension_err(units::kg, "mass")?;
+        scale.dimension_err(units::m, "scale")?;
+        let speed = (crate::constants::G * mass / scale).sqrt();
+        let x = [0.970_004_4, -0.243_087_53];
+        let v = [-0.932_407_4, -0.864_731_46];
+
+        let mut universe = Universe::new();
+        for (position, velocity) in [
+            (x, [-v[0] / 2.0, -v[1] / 2.0]),
+            ([-x[0], -x[1]], [-v[0] / 2.0, -v[1] / 2.0]),
+            ([0.0, 0.0], v),
+        ] {
+            universe.add_object(
+                ObjectBuilder::new_at(position * scale)
+                    .with_velocity(velocity * speed)
+                    .with_mass(mass)
+                    .with_size(1e-3 * scale)
+                    .build()?,
+            );
+        }
+        Ok(universe)
+    }
+
+    /// Bodies on the x axis `separation` apart with relative velocity `v_rel` along y,
+    /// the center of mass resting at the origin
+    fn two_body(
+        m1: Scalar,
+        m2: Scalar,
+        separation: Scalar,
+        v_rel: Scalar,
+    ) -> Result<Universe<2>, DimensionError> {
+        let total = m1 + m2;
+        let mut universe = Universe::new();
+        for (mass, side) in [(m1, -m2 / total), (m2, m1 / total)] {
+            universe.add_object(
+                ObjectBuilder::new_at([side.value(), 0.0] * separation)
+                    .with_velocity([0.0, side.value()] * v_rel)
+                    .with_mass(mass)
+                    .with_size(1e-3 * separation)
+                    .build()?,
+            );
+        }
+        Ok(universe)
+    }
+}
+
+impl Universe<3> {
+    /// Positions of the objects projected onto `plane` with their depths, in object order.
+    /// Drawing them by decreasing depth puts nearer objects on top
+    pub fn project_to_2d(&self, plane: Plane) -> Vec<(Vector<2>, Scalar)> {
+        self.objects
+            .iter()
+            .map(|object| plane.project(object.position))
+            .collect()
+    }
+}
+
+impl<const N: usize, const T: usize> From<[Object<N>; T]> for Universe<N> {
+    fn from(objects: [Object<N>; T]) -> Self {
+        let mut world = Self::new();
+        world.with_objects(objects);
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants, units::*, ObjectAttributes, ObjectBuilder};
+
+    #[test]
+    fn test_clear() {
+        let mut universe = Universe::<2>::new();
+        universe.add_object(ObjectBuilder::new_at([0.0, 0.0] * m).build().unwrap());
+        universe.add_object(ObjectBuilder::new_at([5.0, 0.0] * m).build().unwrap());
+        assert_eq!(universe.len(), 2);
+
+        universe.clear();
+        assert_eq!(universe.len(), 0);
+        assert!(universe.is_empty());
+        universe.step(0.01);
+        assert!(universe.is_empty());
+    }
+
+    #[test]
+    fn test_interpolated_positions() {
+        let mut universe = Universe::<2>::new();
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_velocity([10.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        universe.step(0.01 + 0.5 * STEP);
+
+        let object = &universe.objects()[0];
+        assert_ne!(object.previous_position, object.position);
+        assert_eq!(
+            universe.interpolated_positions(0.0),
+            vec![object.previous_position]
+        );
+        assert_eq!(universe.interpolated_positions(1.0), vec![object.position]);
+        assert!(universe.interpolation_alpha() > 0.0 && universe.interpolation_alpha() < 1.0);
+    }
+
+    #[test]
+    fn test_earth_gravity() {
+        let mut universe = Universe::<2>::new();
+        universe.set_earth_gravity();
+        universe.add_object(ObjectBuilder::new_at([0.0, 0.0] * m).build().unwrap());
+        universe.step(0.1);
+
+        let velocity = universe.objects()[0].velocity();
+        assert_eq!(velocity.dim(), (m / s).dim());
+        assert!((velocity[1] / 0.1 - 9.80665).abs() < 0.01);
+        assert_eq!(velocity[0], 0.0);
+    }
+
+    #[test]
+    fn test_solver_iterations() {
+        let mut universe = Universe::<2>::new();
+        universe.set_solver_iterations(10);
+        universe.set_earth_gravity();
+        universe.add_plane(-Vector::<2>::j, -10.0 * m);
+        for y in [9.0, 7.0, 5.0] {
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, y] * m)
+                    .with_attributes(ObjectAttributes {
+                        restitution_coefficient: 0.0,
+                        ..Default::default()
+                    })
+                    .build()
+                    .unwrap(),
+            );
+        }
+
+        universe.step(2.0);
+        for _ in 0..10 {
+            universe.step(0.1);
+            let heights = universe.objects().iter().map(|o| o.position()[1]);
+            // y points down, each object rests 2 m above the one below it
+            // and the first 1 m above the floor
+            for (below, y) in [11.0].into_iter().chain(heights.clone()).zip(heights) {
+                assert!(2.0 - (below - y) < 1e-3, "{below} {y}");
+            }
+            // What's left is the jitter of a few substeps of gravity
+            for object in universe.objects() {
+                assert!(object.velocity().magnitude() < 1e-2 * m / s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut universe = Universe::<2>::new();
+        universe.record_inputs(true);
+        universe.set_earth_gravity();
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_velocity([3.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        let deleted = universe.add_object(ObjectBuilder::new_at([10.0, 0.0] * m).build().unwrap());
+        universe.add_object(ObjectBuilder::new_at([20.0, 0.0] * m).build().unwrap());
+        universe.step(0.05);
+        universe.delete_object(deleted);
+        universe.add_electric_field([1.0, 0.0] * N / C);
+        universe.step(0.05);
+        universe.remove_objects(|object| object.position()[0] < 15.0);
+        universe.step(0.02);
+
+        let events = universe.take_events();
+        assert_eq!(events.len(), 10);
+        assert!(events[5].time > events[3].time);
+
+        let replayed = Universe::replay(events);
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed.state_hash(), universe.state_hash());
+    }
+
+    #[test]
+    fn test_set_restitution() {
+        let rebound = |restitution: Option<Float>| {
+            let mut universe = Universe::<2>::new();
+            let a = universe.add_object(
+                ObjectBuilder::new_at([0.0, 0.0] * m)
+                    .with_velocity([2.0, 0.0] * m / s)
+                    .build()
+                    .unwrap(),
+            );
+            let b = universe.add_object(ObjectBuilder::new_at([3.0, 0.0] * m).build().unwrap());
+            universe.step(0.1);
+            if let Some(e) = restitution {
+                universe.set_restitution(a, e);
+                universe.set_restitution(b, e);
+            }
+            universe.step(0.9);
+            universe.object(b).unwrap().velocity()[0]
+        };
+
+        assert!((rebound(None) - 2.0).abs() < 1e-3);
+        assert!((rebound(Some(0.0)) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_center_of_momentum_frame() {
+        let drift = |frame| {
+            let mut universe = Universe::<2>::new();
+            universe.set_reference_frame(frame);
+            // Equal masses on a circular orbit of radius 1 m, translating at 1 km/s
+            let mass = 1e12 * kg;
+            let v = (constants::G * mass / (4.0 * m)).sqrt();
+            for side in [-1.0, 1.0] {
+                universe.add_object(
+                    ObjectBuilder::new_at([side, 0.0] * m)
+                        .with_velocity([1000.0, side * v.value()] * m / s)
+                        .with_mass(mass)
+                        .with_size(0.1 * m)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            let energy = |universe: &Universe<2>| {
+                let [a, b] = universe.objects() else {
+                    unreachable!()
+                };
+                let v_rel = b.velocity() - a.velocity();
+                let r = (b.position() - a.position()).magnitude();
+                0.25 * mass * v_rel.squared() - constants::G * mass * mass / r
+            };
+
+            let initial = energy(&universe);
+            for _ in 0..15 {
+                universe.step(1.0);
+            }
+            ((energy(&universe) - initial) / initial).abs().value()
+        };
+
+        let world = drift(Frame::World);
+        let com = drift(Frame::CenterOfMomentum);
+        assert!(com < world / 10.0, "{com} {world}");
+    }
+
+    #[test]
+    fn test_plane_contact_in_center_of_momentum_frame() {
+        let bounce = |frame| {
+            let mut universe = Universe::<2>::new();
+            universe.set_reference_frame(frame);
+            universe.add_plane(Vector::<2>::j, 0.0 * m);
+            let ball = universe.add_object(
+                ObjectBuilder::new_at([0.0, 2.0] * m)
+                    .with_velocity([0.5, -2.0] * m / s)
+                    .with_size(0.5 * m)
+                    .build()
+                    .unwrap(),
+            );
+            // Moves the center of momentum away from the plane
+            universe.add_object(
+                ObjectBuilder::new_at([3.0, 8.0] * m)
+                    .with_velocity([1.0, 0.0] * m / s)
+                    .build()
+                    .unwrap(),
+            );
+            for _ in 0..200 {
+                universe.step(0.01);
+            }
+            let ball = universe.object(ball).unwrap();
+            (ball.position(), ball.velocity())
+        };
+
+        let (x_world, v_world) = bounce(Frame::World);
+        assert!(v_world[1] > 0.0, "{v_world:?}");
+        let (x_com, v_com) = bounce(Frame::CenterOfMomentum);
+        // Positions relative to the moving origin round differently
+        assert!(
+            (x_com - x_world).magnitude() < 1e-2 * m,
+            "{x_com:?} {x_world:?}"
+        );
+        assert!(
+            (v_com - v_world).magnitude() < 1e-3 * m / s,
+            "{v_com:?} {v_world:?}"
+        );
+    }
+
+    #[test]
+    fn test_uniform_force() {
+        let mut universe = Universe::<2>::new();
+        universe.set_uniform_force([2.0, 0.0] * N);
+        for (y, mass) in [(0.0, 1.0), (5.0, 4.0)] {
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, y] * m)
+                    .with_mass(mass * kg)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(0.1);
+
+        let [light, heavy] = universe.objects() else {
+            unreachable!()
+        };
+        assert!((light.velocity()[0] / 0.1 - 2.0).abs() < 0.01);
+        assert!((heavy.velocity()[0] / 0.1 - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_plane() {
+        let mut universe = Universe::<2>::new();
+        universe.set_earth_gravity();
+        universe.add_plane(-Vector::<2>::j, -5.0 * m);
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_size(0.5 * m)
+                .with_attributes(ObjectAttributes {
+                    restitution_coefficient: 0.0,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        );
+        universe.step(2.0);
+
+        let object = &universe.objects()[0];
+        assert!((object.position()[1] - 4.5).abs() < 1e-5);
+        assert!(object.velocity().magnitude() < 0.01 * m / s);
+    }
+
+    #[test]
+    fn test_verify_collisions() {
+        let mut universe = Universe::<2>::new();
+        universe.set_verify_collisions(true);
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_velocity([2.0, 0.5] * m / s)
+                .build()
+                .unwrap(),
+        );
+        universe.add_object(
+            ObjectBuilder::new_at([2.5, 0.0] * m)
+                .with_mass(3.0 * kg)
+                .with_velocity([-1.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        universe.step(0.5);
+        assert!(universe.objects()[1].velocity()[0] > 0.0);
+        assert_eq!(universe.take_collision_errors(), vec![]);
+
+        // A resolver that pushes one object without pushing back on the other
+        let before = universe
+            .objects()
+            .iter()
+            .map(|o| (o.velocity, o.angular_velocity))
+            .collect::<Vec<_>>();
+        universe.objects[0].velocity += [0.0, 1.0] * m / s;
+        universe.verify_conservation(&before, &[0, 1], true, true);
+        let errors = universe.take_collision_errors();
+        assert!(matches!(
+            errors[..],
+            [
+                CollisionVerificationError::EnergyIncrease { .. },
+                CollisionVerificationError::MomentumChange { .. }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_fragmentation() {
+        let mut universe = Universe::<2>::new();
+        universe.set_fragmentation(1.0 * J, 4);
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_mass(10.0 * kg)
+                .with_velocity([5.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        let spin = [0.0, 0.0, 3.0] * rad / s;
+        universe.add_object(
+            ObjectBuilder::new_at([2.05, 0.3] * m)
+                .with_mass(2.0 * kg)
+                .with_angle(0.5)
+                .with_angular_velocity(spin)
+                .build()
+                .unwrap(),
+        );
+        let momentum = |universe: &Universe<2>| {
+            universe
+                .objects()
+                .iter()
+                .fold(Vector::zero() * kg * m / s, |acc, o| {
+                    acc + o.mass() * o.velocity()
+                })
+        };
+        let mass = |universe: &Universe<2>| {
+            universe
+                .objects()
+                .iter()
+                .fold(Scalar::ZERO * kg, |acc, o| acc + o.mass())
+        };
+        let p = momentum(&universe);
+        universe.step(0.02);
+
+        assert_eq!(universe.len(), 5);
+        assert_eq!(universe.objects()[0].mass(), 10.0 * kg);
+        assert!((mass(&universe) - 12.0 * kg).abs() < 1e-4 * kg);
+        assert!((momentum(&universe) - p).magnitude() < 1e-3 * kg * m / s);
+
+        let fragments = &universe.objects()[1..];
+        for (k, a) in fragments.iter().enumerate() {
+            // Four balls of the volume of the original one, even in 2D
+            assert!((a.size() - 4.0f32.cbrt().recip() * m).abs() < 1e-6 * m);
+            assert_eq!(a.angular_velocity(), spin);
+            // Turned further by the spin since the object was added
+            assert_eq!(a.orientation(), fragments[0].orientation());
+            assert!(a.orientation().angle() > 0.5);
+            for b in &fragments[k + 1..] {
+                let gap = a.position().distance(b.position()) - a.size() - b.size();
+                assert!(gap > -1e-3 * m, "{gap}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_free_rotation() {
+        let mut universe = Universe::<3>::new();
+        let ω = [1.0, 1.0, 0.0] * rad / s;
+        universe.add_object(
+            ObjectBuilder::new_at(Vector::zero() * m)
+                .with_angular_velocity(ω)
+                .build()
+                .unwrap(),
+        );
+        universe.step(1.0);
+
+        let object = &universe.objects()[0];
+        assert_eq!(object.angular_velocity(), ω);
+        let orientation = object.orientation();
+        assert!((orientation.angle() - 2.0f32.sqrt()).abs() < 1e-3);
+        assert!((orientation.rotate(ω) - ω).magnitude() < 1e-4 * rad / s);
+    }
+
+    #[test]
+    fn test_tumbling() {
+        let mut universe = Universe::<3>::new();
+        let ω = [1.0, 0.2, 0.5] * rad / s;
+        universe.add_object(
+            ObjectBuilder::new_at(Vector::zero() * m)
+                .with_principal_moments([1.0, 2.0, 3.0] * kg * m * m)
+                .with_angular_velocity(ω)
+                .build()
+                .unwrap(),
+        );
+        let l = universe.objects()[0].angular_momentum();
+        let energy = 0.5 * ω.dot(l);
+        universe.step(2.0);
+
+        let object = &universe.objects()[0];
+        let spin = object.angular_velocity();
+        assert!((spin - ω).magnitude() > 0.1 * rad / s);
+        let r = object.orientation();
+        let body = r.conjugate().rotate(spin);
+        let l_world = r.rotate(body.hadamard([1.0, 2.0, 3.0] * kg * m * m));
+        // Only the f32 rounding of 20000 substeps adds up
+        assert!((l_world - l).magnitude() < l.magnitude() * 1e-3);
+        assert!((0.5 * spin.dot(l_world) - energy).abs() < energy * 5e-3);
+    }
+
+    #[test]
+    fn test_step_dt() {
+        let universe = || {
+            let mut universe = Universe::<2>::new();
+            universe.set_earth_gravity();
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, 0.0] * m)
+                    .with_velocity([1.0, 0.0] * m / s)
+                    .build()
+                    .unwrap(),
+            );
+            universe
+        };
+        let mut a = universe();
+        let mut b = universe();
+        a.step(0.05);
+        b.step_dt(0.05 * s).unwrap();
+        assert_eq!(a.state_hash(), b.state_hash());
+
+        assert!(b.step_dt(0.05 * m).is_err());
+        assert_eq!(a.state_hash(), b.state_hash());
+    }
+
+    #[test]
+    fn test_gravity_scale() {
+        let mut universe = Universe::<2>::new();
+        universe.set_earth_gravity();
+        for (x, gravity_scale) in [(0.0, 0.5), (2.0, 2.0)] {
+            universe.add_object(
+                ObjectBuilder::new_at([x, 0.0] * m)
+                    .with_size(0.1 * m)
+                    .with_mass(1e10 * kg)
+                    .with_attributes(ObjectAttributes {
+                        gravity_scale,
+                        ..Default::default()
+                    })
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(0.01);
+
+        let [a, b] = universe.objects() else {
+            unreachable!()
+        };
+        assert!((b.acc[1] / a.acc[1] - 4.0).abs() < 1e-4);
+        assert!((a.acc[0] + b.acc[0]).abs() < 1e-6);
+        assert!(a.acc[0] > 0.1);
+    }
+
+    #[test]
+    fn test_nearest_object() {
+        let mut universe = Universe::<2>::new();
+        assert_eq!(universe.nearest_object([0.0, 0.0] * m), None);
+        for (x, size) in [(0.0, 0.5), (5.0, 3.0), (-4.0, 1.0)] {
+            universe.add_object(
+                ObjectBuilder::new_at([x, 0.0] * m)
+                    .with_size(size * m)
+                    .build()
+                    .unwrap(),
+            );
+        }
+
+        // Closer to the center of the first object, closer to the surface of the second
+        let (id, distance) = universe.nearest_object([1.5, 0.0] * m).unwrap();
+        assert_eq!(id, universe.ids()[1]);
+        assert!((distance - 0.5 * m).abs() < 1e-6 * m);
+
+        let (id, distance) = universe.nearest_object([-4.5, 0.0] * m).unwrap();
+        assert_eq!(id, universe.ids()[2]);
+        assert!((distance + 0.5 * m).abs() < 1e-6 * m);
+
+        // A diverged object doesn't panic the search and is never the nearest
+        universe.add_object(
+            ObjectBuilder::new_at([Float::NAN, 0.0] * m)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            universe.nearest_object([1.5, 0.0] * m).unwrap().0,
+            universe.ids()[1]
+        );
+    }
+
+    #[test]
+    fn test_nearest_object_incremental() {
+        let mut universe = Universe::<2>::new();
+        universe.set_incremental_broadphase(true);
+        for k in 0..200 {
+            let (x, y) = ((k * 37 % 101) as Float, (k * 53 % 89) as Float);
+            universe.add_object(
+                ObjectBuilder::new_at([x, y] * m)
+                    .with_size((0.2 + (k % 7) as Float) * m)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step_once();
+
+        for k in 0..50 {
+            let point = [(k * 29 % 113) as Float - 5.0, (k * 17 % 97) as Float] * m;
+            let distances = universe
+                .objects()
+                .iter()
+                .map(|o| point.distance(o.position()) - o.size());
+            let closest = distances.fold(Float::INFINITY * m, |a, b| if b < a { b } else { a });
+            let (id, distance) = universe.nearest_object(point).unwrap();
+            assert_eq!(distance, closest);
+            let object = universe.object(id).unwrap();
+            assert_eq!(point.distance(object.position()) - object.size(), closest);
+        }
+    }
+
+    #[test]
+    fn test_total_charge() {
+        let mut universe = Universe::<2>::new();
+        assert_eq!(universe.total_charge(), 0.0 * C);
+        for (x, q) in [(0.0, 1.5e-6), (5.0, -1.5e-6)] {
+            universe.add_object(
+                ObjectBuilder::new_at([x, 0.0] * m)
+                    .with_charge(q * C)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        assert!(universe.is_neutral(1e-12 * C));
+
+        universe.add_object(
+            ObjectBuilder::new_at([10.0, 0.0] * m)
+                .with_charge(-2e-6 * C)
+                .build()
+                .unwrap(),
+        );
+        assert!(!universe.is_neutral(1e-12 * C));
+        assert!((universe.total_charge() + 2e-6 * C).abs() < 1e-12 * C);
+    }
+
+    #[test]
+    fn test_soft_body() {
+        let mut universe = Universe::<2>::new();
+        universe.set_earth_gravity();
+        universe.add_plane(-Vector::<2>::j, -1.5 * m);
+        let ids = universe.add_soft_body(
+            BoundingBox {
+                min: [0.0, 0.0] * m,
+                max: [1.0, 1.0] * m,
+            },
+            [2, 2],
+            1.0 * kg,
+            200.0 * N / m,
+        );
+        assert_eq!(ids.len(), 4);
+        assert_eq!(universe.springs.len(), 6);
+
+        // Gravity points along +y, so ids[0] is above ids[2]
+        let mut lowest: Float = 1.0;
+        for _ in 0..100 {
+            universe.step(0.01);
+            let position = |id: ObjectID| universe.object(id).unwrap().position();
+            lowest = lowest.min(position(ids[2])[1] - position(ids[0])[1]);
+            let objects = universe.objects();
+            for spring in &universe.springs {
+                let length = objects[spring.a]
+                    .position()
+                    .distance(objects[spring.b].position());
+                assert!((length / spring.rest_length - 1.0).abs() < 0.2);
+            }
+        }
+        assert!(lowest < 0.99, "{lowest}");
+    }
+
+    #[test]
+    fn test_spring_forces() {
+        let objects = [[0.0, 0.0], [3.0, 4.0], [3.0, 4.0]]
+            .map(|x| ObjectBuilder::new_at(x * m).build().unwrap());
+        let spring = |a, b, rest_length| Spring {
+            a,
+            b,
+            rest_length,
+            stiffness: 2.0 * N / m,
+        };
+        let forces = Universe::spring_forces(&objects, &[spring(0, 1, 4.0 * m), spring(1, 2, m)]);
+        assert!((forces[0] - [1.2, 1.6] * N).magnitude() < 1e-5 * N);
+        assert!((forces[1] + forces[0]).magnitude() < 1e-5 * N);
+        assert_eq!(forces[2], Vector::zero() * N);
+    }
+
+    #[test]
+    #[should_panic(expected = "to itself with a spring")]
+    fn test_spring_to_itself() {
+        let mut universe = Universe::<2>::new();
+        let id = universe.add_object(ObjectBuilder::new_at([0.0, 0.0] * m).build().unwrap());
+        universe.add_spring(id, id, 1.0 * N / m);
+    }
+
+    #[test]
+    fn test_raycast_down() {
+        let mut universe = Universe::<2>::new();
+        universe.set_earth_gravity();
+        universe.add_plane(-Vector::<2>::j, -5.0 * m);
+        let id = universe.add_object(
+            ObjectBuilder::new_at([0.0, 1.0] * m)
+                .with_size(0.5 * m)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(
+            universe.raycast_down(id, 10.0 * m),
+            Some((ColliderID::Plane(0), 3.5 * m))
+        );
+        assert_eq!(universe.raycast_down(id, 3.0 * m), None);
+
+        let below = universe.add_object(ObjectBuilder::new_at([0.0, 3.0] * m).build().unwrap());
+        assert_eq!(
+            universe.raycast_down(id, 10.0 * m),
+            Some((ColliderID::Object(below), 0.5 * m))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot cast a ray along a zero direction")]
+    fn test_raycast_zero_direction() {
+        let mut universe = Universe::<2>::new();
+        universe.add_object(ObjectBuilder::new_at([1.0, 0.0] * m).build().unwrap());
+        universe.raycast([0.0, 0.0] * m, Vector::zero(), 10.0 * m, None);
+    }
+
+    #[test]
+    fn test_contact_solver_order_independent() {
+        let pile_up = |solver, order: [usize; 3]| {
+            let balls = [(-2.05, 3.0, 1.0), (0.0, 0.0, 2.0), (2.05, -1.0, 3.0)];
+            let mut universe = Universe::<2>::new();
+            universe.set_contact_solver(solver);
+            universe.set_solver_iterations(20);
+            for i in order {
+                let (x, v, mass) = balls[i];
+                universe.add_object(
+                    ObjectBuilder::new_at([x, 0.0] * m)
+                        .with_velocity([v, 0.0] * m / s)
+                        .with_mass(mass * kg)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            universe.step(0.02);
+            let mut velocities = order.map(|_| 0.0);
+            for (object, i) in universe.objects().iter().zip(order) {
+                velocities[i] = object.velocity()[0];
+            }
+            velocities
+        };
+
+        let a = pile_up(ContactSolver::Simultaneous, [0, 1, 2]);
+        let b = pile_up(ContactSolver::Simultaneous, [2, 0, 1]);
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-5, "{a:?} {b:?}");
+        }
+        assert_ne!(a, [3.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_deepest_contacts_first() {
+        let pile_up = |deepest_first, order: [usize; 3]| {
+            // The contact on the right is five times deeper than the one on the left
+            let balls = [(0.0, 3.0), (1.95, 0.0), (3.7, -1.0)];
+            let mut universe = Universe::<2>::new();
+            universe.set_deepest_contacts_first(deepest_first);
+            for i in order {
+                let (x, v) = balls[i];
+                universe.add_object(
+                    ObjectBuilder::new_at([x, 0.0] * m)
+                        .with_velocity([v, 0.0] * m / s)
+                        .with_restitution(0.0)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            universe.resolve_collisions(Vector::zero() * m, Vector::zero() * m / s);
+            let mut velocities = order.map(|_| 0.0);
+            for (object, i) in universe.objects().iter().zip(order) {
+                velocities[i] = object.velocity()[0];
+            }
+            velocities
+        };
+
+        assert_ne!(pile_up(false, [0, 1, 2]), pile_up(false, [2, 1, 0]));
+        let a = pile_up(true, [0, 1, 2]);
+        let b = pile_up(true, [2, 1, 0]);
+        assert_eq!(a, b);
+        // The deep contact is solved first and the shallow one last, which leaves it exact
+        assert!((a[0] - a[1]).abs() < 1e-6, "{a:?}");
+        assert!(a[1] > a[2], "{a:?}");
+    }
+
+    #[test]
+    fn test_lifetime() {
+        let mut universe = Universe::<2>::new();
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_attributes(ObjectAttributes {
+                    lifetime: Some(1.0 * s),
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        );
+        universe.add_object(ObjectBuilder::new_at([5.0, 0.0] * m).build().unwrap());
+
+        universe.step(0.9);
+        assert_eq!(universe.len(), 2);
+        let lifetime = universe.objects()[0].attributes().lifetime.unwrap();
+        assert!((lifetime - 0.1 * s).abs() < 1e-3 * s);
+
+        universe.step(0.2);
+        assert_eq!(universe.len(), 1);
+        assert_eq!(universe.objects()[0].position()[0], 5.0);
+        assert_eq!(universe.objects()[0].attributes().lifetime, None);
+    }
+
+    #[test]
+    fn test_1d_gravity() {
+        let mut universe = Universe::<1>::new();
+        for x in [0.0, 2.0] {
+            universe.add_object(
+                ObjectBuilder::new_at([x] * m)
+                    .with_size(0.1 * m)
+                    .with_mass(1e10 * kg)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(0.1);
+        let [a, b] = universe.objects() else {
+            unreachable!()
+        };
+        assert!(a.position()[0] > 0.0);
+        assert!(b.position()[0] < 2.0);
+        assert!(a.velocity()[0] > 0.0);
+    }
+
+    #[test]
+    fn test_1d_charges() {
+        let mut universe = Universe::<1>::new();
+        for x in [0.0, 2.0] {
+            universe.add_object(
+                ObjectBuilder::new_at([x] * m)
+                    .with_size(0.1 * m)
+                    .with_charge(1e-4 * C)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(0.1);
+        let [a, b] = universe.objects() else {
+            unreachable!()
+        };
+        assert!(a.position()[0] < 0.0);
+        assert!(b.position()[0] > 2.0);
+        assert!((a.position()[0] + b.position()[0] - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_adaptive_step() {
+        let run = |adaptive| {
+            let mut universe = Universe::<2>::new();
+            universe.set_adaptive_step(adaptive);
+            // Equal masses on an orbit of eccentricity 0.95, released at apocenter
+            let mass = 1e12 * kg;
+            let v = (constants::G * 2.0 * mass * 0.05 / (2.0 * m)).sqrt() / 2.0;
+            for side in [-1.0, 1.0] {
+                universe.add_object(
+                    ObjectBuilder::new_at([side, 0.0] * m)
+                        .with_velocity([0.0, side * v.value()] * m / s)
+                        .with_mass(mass)
+                        .with_size(0.01 * m)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            let energy = |universe: &Universe<2>| {
+                let [a, b] = universe.objects() else {
+                    unreachable!()
+                };
+                let v_rel = b.velocity() - a.velocity();
+                let r = (b.position() - a.position()).magnitude();
+                0.25 * mass * v_rel.squared() - constants::G * mass * mass / r
+            };
+
+            let initial = energy(&universe);
+            let mut drift: Float = 0.0;
+            for _ in 0..20 {
+                universe.step(0.05);
+                drift = drift.max(((energy(&universe) - initial) / initial).abs().value());
+            }
+            assert!((universe.time() - 1.0 * s).abs() < 1e-5 * s);
+            (drift, universe.substeps())
+        };
+
+        let (fixed, fixed_cost) = run(None);
+        let (adaptive, adaptive_cost) = run(Some(AdaptiveStep {
+            softening: 1e-6 * m,
+            min: 1e-7 * s,
+            max: 1e-3 * s,
+        }));
+        assert!(adaptive_cost <= fixed_cost, "{adaptive_cost} {fixed_cost}");
+        assert!(adaptive < fixed / 10.0, "{adaptive} {fixed}");
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut universe = Universe::<1>::new();
+        universe.add_object(
+            ObjectBuilder::new_at([0.0] * m)
+                .with_velocity([1.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        universe.pause();
+        assert!(universe.is_paused());
+        universe.step(1.0);
+        assert_eq!(universe.time(), 0.0 * s);
+        assert_eq!(universe.objects()[0].position(), [0.0] * m);
+
+        universe.step_once();
+        assert_eq!(universe.time(), h());
+        assert_eq!(universe.substeps(), 1);
+        assert!((universe.objects()[0].position() - [STEP] * m).is_zero());
+
+        universe.resume();
+        universe.step(0.01);
+        assert!(universe.substeps() > 1);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut universe = Universe::<2>::new();
+        let ids = (0..4)
+            .map(|i| {
+                universe.add_object(
+                    ObjectBuilder::new_at([3.0 * i as Float, 0.0] * m)
+                        .build()
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let player = ids[2];
+        universe.retain(|id, _| id == player);
+        assert_eq!(universe.len(), 1);
+        assert_eq!(universe.ids(), [player]);
+        let survivor = universe.object(player).unwrap();
+        assert_eq!(survivor.position(), [6.0, 0.0] * m);
+        for id in [ids[0], ids[1], ids[3]] {
+            assert!(universe.object(id).is_none());
+        }
+
+        // Reused slots come with a new generation, so the removed IDs stay dead
+        let added = (0..3)
+            .map(|_| universe.add_object(ObjectBuilder::new_at([0.0, 5.0] * m).build().unwrap()))
+            .collect::<Vec<_>>();
+        assert!(added.iter().all(|id| !ids.contains(id)));
+        assert!(universe.object(ids[0]).is_none());
+        assert_eq!(universe.object(player).unwrap().position(), [6.0, 0.0] * m);
+        universe.delete_object(added[0]);
+        assert_eq!(universe.object(player).unwrap().position(), [6.0, 0.0] * m);
+        assert_eq!(
+            universe.object(added[1]).unwrap().position(),
+            [0.0, 5.0] * m
+        );
+    }
+
+    #[test]
+    fn test_potentials() {
+        let mut universe = Universe::<2>::new();
+        let (q, mass) = (2e-6 * C, 5e10 * kg);
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_charge(q)
+                .with_mass(mass)
+                .build()
+                .unwrap(),
+        );
+        let x = [3.0, 4.0] * m;
+
+        let electric = universe.electric_potential();
+        assert_eq!(electric.dim(), V.dim());
+        let expected = constants::k_e() * q / (5.0 * m);
+        assert!(((electric.at(x).unwrap() - expected) / expected).abs() < 1e-5);
+        assert_eq!(electric.gradient().dim(), (V / m).dim());
+
+        let gravitational = universe.gravitational_potential();
+        assert_eq!(gravitational.dim(), (J / kg).dim());
+        let expected = -constants::G * mass / (5.0 * m);
+        assert!(((gravitational.at(x).unwrap() - expected) / expected).abs() < 1e-5);
+        assert_eq!(gravitational.gradient().dim(), (m / s.powi(2)).dim());
+    }
+
+    #[test]
+    fn test_collision_groups() {
+        let collide = |groups: [Option<u32>; 2]| {
+            let mut universe = Universe::<2>::new();
+            for (x, group_id) in [0.0, 1.5].into_iter().zip(groups) {
+                universe.add_object(
+                    ObjectBuilder::new_at([x, 0.0] * m)
+                        .with_velocity([1.0 - x, 0.0] * m / s)
+                        .with_attributes(ObjectAttributes {
+                            group_id,
+                            ..Default::default()
+                        })
+                        .build()
+                        .unwrap(),
+                );
+            }
+            universe.resolve_collisions(Vector::zero() * m, Vector::zero() * m / s);
+            universe.objects()[0].velocity() != [1.0, 0.0] * m / s
+        };
+        assert!(!collide([Some(1), Some(1)]));
+        assert!(collide([Some(1), Some(2)]));
+        assert!(collide([Some(1), None]));
+        assert!(collide([None, None]));
+    }
+
+    #[test]
+    fn test_config() {
+        let config = UniverseConfig {
+            gravitational_field: [0.0, 9.8] * N / kg,
+            solver_iterations: 4,
+            contact_solver: ContactSolver::Simultaneous,
+            ..Default::default()
+        };
+        let run = || {
+            let mut universe = Universe::<2>::with_config(config);
+            for (x, v) in [(0.0, 2.0), (2.5, -1.0), (5.0, 0.0)] {
+                universe.add_object(
+                    ObjectBuilder::new_at([x, 0.0] * m)
+                        .with_velocity([v, 0.0] * m / s)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            universe.step(0.5);
+            universe
+        };
+        let (a, b) = (run(), run());
+        assert_eq!(a.config(), config);
+        assert_eq!(a.state_hash(), b.state_hash());
+    }
+
+    #[test]
+    fn test_two_body_circular() {
+        let (mass, distance) = (1e11 * kg, 1.0 * m);
+        let mut universe = Universe::two_body_circular(mass, mass, distance).unwrap();
+        let period = 2.0 * crate::PI * (distance.powi(3) / (constants::G * 2.0 * mass)).sqrt();
+        for _ in 0..20 {
+            universe.step((period / 20.0).value());
+            let [a, b] = universe.objects() else {
+                unreachable!()
+            };
+            let separation = (b.position() - a.position()).magnitude();
+            assert!(
+                ((separation - distance) / distance).abs() < 1e-3,
+                "{separation:?}"
+            );
+        }
+        assert!(universe.center_of_mass().magnitude() < 1e-4 * m);
+
+        let ellipse = Universe::kepler_ellipse(mass, mass, distance, 0.5).unwrap();
+        let [a, b] = ellipse.objects() else {
+            unreachable!()
+        };
+        assert!(((b.position() - a.position()).magnitude() - 0.5 * m).abs() < 1e-6 * m);
+    }
+
+    #[test]
+    fn test_figure_eight() {
+        let (mass, scale) = (1e11 * kg, 1.0 * m);
+        let mut universe = Universe::figure_eight(mass, scale).unwrap();
+        let start = universe
+            .objects()
+            .iter()
+            .map(|object| object.position())
+            .collect::<Vec<_>>();
+        let period = 6.325_914 * (scale.powi(3) / (constants::G * mass)).sqrt();
+        for _ in 0..50 {
+            universe.step((period / 50.0).value());
+            for object in universe.objects() {
+                assert!(object.position().magnitude() < 1.2 * scale);
+            }
+        }
+        for (object, start) in universe.objects().iter().zip(start) {
+            assert!((object.position() - start).magnitude() < 0.05 * scale);
+        }
+    }
+
+    #[test]
+    fn test_valid_coefficients_conserve_energy() {
+        for e in [0.0, 0.3, 1.0] {
+            for μ in [0.0, 0.5, 2.0] {
+                let mut universe = Universe::<2>::new();
+                universe.set_verify_collisions(true);
+                for (x, y, v) in [
+                    (0.0, 0.0, [2.0, 0.5]),
+                    (2.5, 0.6, [-1.0, 0.0]),
+                    (4.0, -1.0, [0.0, 1.0]),
+                ] {
+                    universe.add_object(
+                        ObjectBuilder::new_at([x, y] * m)
+                            .with_velocity(v * m / s)
+                            .with_restitution(e)
+                            .with_friction(μ)
+                            .build()
+                            .unwrap(),
+                    );
+                }
+                universe.step(1.0);
+                assert_eq!(universe.take_collision_errors(), vec![], "e = {e}, μ = {μ}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_broadphase() {
+        let mut universe = Universe::<2>::new();
+        universe.set_incremental_broadphase(true);
+        let mut seed: u32 = 7;
+        let mut random = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as Float / (1 << 24) as Float - 0.5
+        };
+        for _ in 0..40 {
+            universe.add_object(
+                ObjectBuilder::new_at([20.0 * random(), 20.0 * random()] * m)
+                    .with_velocity([5.0 * random(), 5.0 * random()] * m / s)
+                    .with_size(0.5 * m)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let mut found = 0;
+        for _ in 0..100 {
+            universe.step_once();
+            let incremental = universe.broad_phase();
+            assert_eq!(incremental, possible_collisions(&universe.objects, 8));
+            found += incremental.len();
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn test_rolling() {
+        let θ: Float = 0.3;
+        let run = |rolling: bool| {
+            let mut universe = Universe::<2>::new();
+            universe.add_gravitational_field([0.0, -9.8] * N / kg);
+            let normal = Vector([-θ.sin(), θ.cos()], Dimension::NONE);
+            universe.add_plane(normal, 0.0 * m);
+            universe.add_object(
+                ObjectBuilder::new_at(normal * (1.0 * m))
+                    .with_attributes(ObjectAttributes {
+                        restitution_coefficient: 0.0,
+                        rolling,
+                        ..Default::default()
+                    })
+                    .build()
+                    .unwrap(),
+            );
+            universe.step(1.0);
+            let down = Vector([-θ.cos(), -θ.sin()], Dimension::NONE);
+            let object = &universe.objects()[0];
+            (
+                object.velocity().dot(down).value(),
+                object.angular_velocity()[2],
+            )
+        };
+
+        let g_sin = 9.8 * θ.sin();
+        let (sliding, spin) = run(false);
+        assert!((sliding - g_sin).abs() < 0.02 * g_sin, "{sliding}");
+        assert_eq!(spin, 0.0);
+
+        // A disk has I = m r² / 2
+        let (rolling, spin) = run(true);
+        assert!((rolling - g_sin / 1.5).abs() < 0.02 * g_sin, "{rolling}");
+        assert!((spin.abs() - rolling).abs() < 1e-3 * rolling, "{spin}");
+    }
+
+    #[test]
+    fn test_friction_spins() {
+        // A ball thrown sliding on a floor: friction slows it down and spins it up until its
+        // contact point stops, then it rolls at v = ω r with v = 5/7 of the initial speed
+        let mut universe = Universe::<3>::new();
+        universe.add_gravitational_field([0.0, -9.8, 0.0] * N / kg);
+        universe.add_plane(Vector([0.0, 1.0, 0.0], Dimension::NONE), 0.0 * m);
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 1.0, 0.0] * m)
+                .with_velocity([2.0, 0.0, 0.0] * m / s)
+                .with_attributes(ObjectAttributes {
+                    restitution_coefficient: 0.0,
+                    friction_coefficient: 0.5,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        );
+        universe.step(2.0);
+        let object = &universe.objects()[0];
+        let (v, ω) = (object.velocity()[0], object.angular_velocity()[2]);
+        assert!((v - 2.0 * 5.0 / 7.0).abs() < 0.05, "{v}");
+        assert!((v + ω).abs() < 1e-2 * v, "{v} {ω}");
+    }
+
+    #[test]
+    fn test_rotating_frame() {
+        let ω = [0.0, 0.0, 1.0] * rad / s;
+        // The fictitious forces depend on the position and velocity in the world,
+        // whichever frame the objects are integrated in
+        for frame in [Frame::World, Frame::CenterOfMomentum] {
+            let mut universe = Universe::<2>::new();
+            universe.set_reference_frame(frame);
+            universe.set_rotating_frame(ω);
+            // Drifting at `drift` in the inertial frame, which the rotating frame sees as
+            // `drift - ω×r`
+            let (r0, drift) = ([1.0, 0.0] * m, [0.0, 0.5] * m / s);
+            let frame_velocity = Vector([0.0, 1.0], Dimension::NONE) * (1.0 * m / s);
+            universe.add_object(
+                ObjectBuilder::new_at(r0)
+                    .with_velocity(drift - frame_velocity)
+                    .build()
+                    .unwrap(),
+            );
+            for _ in 0..10 {
+                universe.step(0.1);
+                let time = universe.time();
+                let inertial = universe.objects()[0].position().rotate(time * ω[2] / s);
+                assert!(
+                    (inertial - (r0 + drift * time)).magnitude() < 1e-3 * m,
+                    "{frame:?} {inertial:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_position_velocity() {
+        let mut universe = Universe::<2>::new();
+        let id = universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_velocity([1.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        universe.step(0.1);
+        universe.set_position(id, [5.0, -3.0] * m);
+        assert_eq!(universe.objects()[0].position(), [5.0, -3.0] * m);
+        assert_eq!(universe.interpolated_positions(0.5), vec![[5.0, -3.0] * m]);
+
+        universe.set_velocity(id, [0.0, 2.0] * m / s);
+        universe.step_once();
+        let moved = universe.objects()[0].position() - [5.0, -3.0] * m;
+        assert!((moved - [0.0, 2.0 * STEP] * m).is_zero(), "{moved:?}");
+
+        let mut object = universe.objects()[0].clone();
+        assert!(object.set_position([1.0, 1.0] * s).is_err());
+        assert!(object.set_velocity([1.0, 1.0] * m).is_err());
+    }
+
+    #[test]
+    fn test_pair_forces() {
+        let objects = [
+            ([0.0, 0.0], 1e10, 2e-6),
+            ([3.0, 1.0], 5e9, -1e-6),
+            ([-2.0, 4.0], 2e10, 0.0),
+            ([1.0, -5.0], 1e9, 3e-6),
+        ]
+        .map(|(x, mass, q)| {
+            ObjectBuilder::new_at(x * m)
+                .with_mass(mass * kg)
+                .with_charge(q * C)
+                .build()
+                .unwrap()
+        });
+
+        // Both evaluate each pair the same way and sum in the order of the objects
+        for compensated in [false, true] {
+            let forces = Universe::pair_forces(&objects, compensated);
+            for (i, &force) in forces.iter().enumerate() {
+                let naive = Universe::pair_forces_on(&objects, i, compensated);
+                assert_eq!(force, naive, "{i}");
+            }
+        }
+        let forces = Universe::pair_forces(&objects, false);
+        let total = forces.iter().fold(Vector::zero() * N, |acc, &f| acc + f);
+        assert!(total.magnitude() < 1e-6 * forces[0].magnitude());
+    }
+
+    #[test]
+    fn test_speed_limit() {
+        let mut universe = Universe::<2>::new();
+        universe.add_electric_field([1e12, 0.0] * N / C);
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_charge(1.0 * C)
+                .build()
+                .unwrap(),
+        );
+        let limit = universe.config().speed_limit.unwrap();
+        assert!(limit < crate::constants::c);
+        for _ in 0..20 {
+            universe.step(0.001);
+            let speed = universe.objects()[0].velocity().magnitude();
+            assert!(speed <= limit, "{speed:?}");
+            assert!(universe.objects()[0].lorentz_factor().is_finite());
+        }
+        let speed = universe.objects()[0].velocity().magnitude();
+        assert!(speed > 0.999 * limit, "{speed:?}");
+
+        universe.set_speed_limit(Some(1.0 * m / s));
+        universe.step(0.001);
+        assert!(universe.objects()[0].velocity().magnitude() <= 1.0 * m / s);
+
+        // Half the momentum moves with the frame, the limit still holds in the world
+        let mut universe = Universe::<2>::new();
+        universe.set_reference_frame(Frame::CenterOfMomentum);
+        universe.set_speed_limit(Some(2.0 * m / s));
+        for (x, v) in [(0.0, 3.0), (100.0, 0.0)] {
+            universe.add_object(
+                ObjectBuilder::new_at([x, 0.0] * m)
+                    .with_velocity([v, 0.0] * m / s)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(0.001);
+        let speed = universe.objects()[0].velocity().magnitude();
+        assert!((speed - 2.0 * m / s).abs() < 1e-5 * m / s, "{speed:?}");
+    }
+
+    #[test]
+    fn test_track() {
+        let mut universe = Universe::<2>::new();
+        let ids = [0.0, 1.0, 2.0].map(|y| {
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, y] * m)
+                    .with_velocity([1.0, 0.0] * m / s)
+                    .build()
+                    .unwrap(),
+            )
+        });
+        universe.track(ids[1]);
+        universe.set_trajectory_capacity(5);
+        for _ in 0..3 {
+            universe.step(0.01);
+        }
+        let samples = universe.tracked_trajectory(ids[1]);
+        assert_eq!(samples.len(), 3);
+        assert!(universe.tracked_trajectory(ids[0]).is_empty());
+        assert!(universe.tracked_trajectory(ids[2]).is_empty());
+        for (time, position) in samples {
+            assert!((*position - [time.value(), 1.0] * m).magnitude() < 1e-5 * m);
+        }
+
+        for _ in 0..20 {
+            universe.step(0.01);
+        }
+        let samples = universe.tracked_trajectory(ids[1]);
+        assert_eq!(samples.len(), 5);
+        assert_eq!(samples.last().unwrap().1, universe.objects()[1].position());
+        assert!((samples[0].0 - 0.19 * s).abs() < 1e-5);
+
+        // Deleting an object before the tracked one keeps its ID
+        universe.delete_object(ids[0]);
+        assert_eq!(universe.tracked_trajectory(ids[1]).len(), 5);
+
+        // A rejected ID isn't recorded, so the events still replay
+        universe.record_inputs(true);
+        let missing = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            universe.track(ids[0]);
+        }));
+        assert!(missing.is_err());
+        assert!(universe.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_project_to_2d() {
+        let mut universe = Universe::<3>::new();
+        for position in [[1.0, 2.0, 5.0], [3.0, -1.0, -2.0], [0.0, 4.0, 1.0]] {
+            universe.add_object(ObjectBuilder::new_at(position * m).build().unwrap());
+        }
+        let projected = universe.project_to_2d(Plane::XY);
+        assert_eq!(
+            projected.iter().map(|&(p, _)| p).collect::<Vec<_>>(),
+            vec![[1.0, 2.0] * m, [3.0, -1.0] * m, [0.0, 4.0] * m]
+        );
+        let mut by_depth = (0..3).collect::<Vec<_>>();
+        by_depth.sort_by(|&a, &b| projected[b].1.value().total_cmp(&projected[a].1.value()));
+        assert_eq!(by_depth, [0, 2, 1]);
+        assert_eq!(projected[0].1, 5.0 * m);
+
+        let (p, depth) = universe.project_to_2d(Plane::YZ)[1];
+        assert_eq!((p, depth), ([-1.0, -2.0] * m, 3.0 * m));
+        let (p, depth) = universe.project_to_2d(Plane::ZX)[2];
+        assert_eq!((p, depth), ([1.0, 0.0] * m, 4.0 * m));
+    }
+
+    #[test]
+    fn test_radial_impulse() {
+        let mut universe = Universe::<2>::new();
+        for position in [[1.0, 0.0], [0.0, -3.0], [-6.0, 0.0], [0.0, 12.0]] {
+            universe.add_object(
+                ObjectBuilder::new_at(position * m)
+                    .with_mass(2.0 * kg)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.apply_radial_impulse([0.0, 0.0] * m, 10.0 * kg * m / s, 10.0 * m);
+
+        let velocities = universe
+            .objects()
+            .iter()
+            .map(|o| o.velocity())
+            .collect::<Vec<_>>();
+        assert!((velocities[0] - [4.5, 0.0] * m / s).magnitude() < 1e-5 * m / s);
+        assert!((velocities[1] - [0.0, -3.5] * m / s).magnitude() < 1e-5 * m / s);
+        assert!((velocities[2] - [-2.0, 0.0] * m / s).magnitude() < 1e-5 * m / s);
+        assert!(velocities[3].is_zero());
+
+        universe.apply_impulse(universe.ids()[3], [0.0, 4.0] * kg * m / s);
+        assert_eq!(universe.objects()[3].velocity(), [0.0, 2.0] * m / s);
+    }
+
+    #[test]
+    fn test_fast_subdivisions() {
+        // A light moon circling a heavy planet once every 20 substeps
+        let orbit = |subdivisions| {
+            let (radius, period) = (1.0, 20.0 * STEP);
+            let speed = 2.0 * crate::PI * radius / period;
+            let mass = speed * speed * radius / crate::constants::G.value();
+            let mut universe = Universe::<2>::new();
+            universe.set_fast_subdivisions(subdivisions);
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, 0.0] * m)
+                    .with_mass(mass * kg)
+                    .with_size(0.01 * m)
+                    .build()
+                    .unwrap(),
+            );
+            universe.add_object(
+                ObjectBuilder::new_at([radius, 0.0] * m)
+                    .with_velocity([0.0, speed] * m / s)
+                    .with_size(0.01 * m)
+                    .with_attributes(ObjectAttributes {
+                        fast: true,
+                        ..Default::default()
+                    })
+                    .build()
+                    .unwrap(),
+            );
+            let mut error: Float = 0.0;
+            for _ in 0..40 {
+                universe.step_once();
+                let [planet, moon] = [0, 1].map(|i| universe.objects()[i].position());
+                error = error.max(((moon - planet).magnitude() - radius * m).abs().value());
+            }
+            (error, universe.substeps())
+        };
+
+        let (coarse, substeps) = orbit(1);
+        let (fine, fine_substeps) = orbit(10);
+        assert_eq!(substeps, fine_substeps);
+        assert!(fine < coarse / 10.0, "{fine} {coarse}");
+    }
+
+    #[test]
+    fn test_sleeping() {
+        let mut universe = Universe::<2>::new();
+        universe.set_earth_gravity();
+        universe.set_sleeping(true, 0.05 * m / s, 0.5 * s);
+        universe.set_solver_iterations(10);
+        universe.add_plane(-Vector::<2>::j, -10.0 * m);
+        for y in [9.0, 7.0] {
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, y] * m)
+                    .with_size(1.0 * m)
+                    .with_restitution(0.0)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(3.0);
+        assert!(universe.objects().iter().all(Object::is_asleep));
+
+        let settled = universe.state_hash();
+        universe.step(1.0);
+        assert_eq!(universe.state_hash(), settled);
+
+        let striker = universe.add_object(
+            ObjectBuilder::new_at([4.0, 7.0] * m)
+                .with_size(1.0 * m)
+                .with_velocity([-5.0, 0.0] * m / s)
+                .with_attributes(ObjectAttributes {
+                    gravity_scale: 0.0,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap(),
+        );
+        universe.step(0.5);
+        assert!(universe.objects()[1].position()[0] < -0.1);
+        assert!(universe.object(striker).unwrap().velocity()[0] > -5.0);
+    }
+
+    #[test]
+    fn test_massless_kinetic_energy() {
+        let mut universe = Universe::<2>::new();
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_mass(2.0 * kg)
+                .with_velocity([3.0, 0.0] * m / s)
+                .build()
+                .unwrap(),
+        );
+        universe.add_object(
+            ObjectBuilder::new_at([5.0, 0.0] * m)
+                .with_velocity(crate::constants::c * Vector::<2>::j)
+                .massless()
+                .build()
+                .unwrap(),
+        );
+        let energy = universe.total_kinetic_energy();
+        assert!(energy.is_finite(), "{energy:?}");
+        assert!((energy - 9.0 * J).abs() < 1e-3 * J);
+    }
+
+    #[test]
+    fn test_sleeping_pairs_and_removal() {
+        let settled = |incremental: bool| {
+            let mut universe = Universe::<2>::new();
+            universe.set_earth_gravity();
+            universe.set_sleeping(true, 0.05 * m / s, 0.5 * s);
+            universe.set_solver_iterations(10);
+            universe.set_incremental_broadphase(incremental);
+            universe.add_plane(-Vector::<2>::j, -10.0 * m);
+            let ids = [9.0, 7.0].map(|y| {
+                universe.add_object(
+                    ObjectBuilder::new_at([0.0, y] * m)
+                        .with_restitution(0.0)
+                        .build()
+                        .unwrap(),
+                )
+            });
+            universe.step(3.0);
+            assert!(universe.objects().iter().all(Object::is_asleep));
+            (universe, ids)
+        };
+
+        // The stack is touching but asleep, so the broad phase has nothing to report
+        for incremental in [false, true] {
+            let (mut universe, _) = settled(incremental);
+            assert!(universe.broad_phase().is_empty());
+            universe.objects[1].wake();
+            assert_eq!(universe.broad_phase(), vec![(0, 1)]);
+        }
+
+        // Taking the bottom object away wakes the one resting on it, which falls
+        let (mut universe, [bottom, top]) = settled(false);
+        let height = universe.object(top).unwrap().position()[1];
+        universe.delete_object(bottom);
+        assert!(!universe.object(top).unwrap().is_asleep());
+        universe.step(0.2);
+        assert!(universe.object(top).unwrap().position()[1] > height + 0.1);
+    }
+
+    #[test]
+    fn test_compensated_summation() {
+        let at = |x: Float, mass: Float| {
+            ObjectBuilder::new_at([x, 0.0] * m)
+                .with_mass(mass * kg)
+                .build()
+                .unwrap()
+        };
+        // Each light pull is under half an ulp of the heavy one and rounds away on its own
+        let mut objects = vec![at(0.0, 1.0), at(1.0, 1e10)];
+        objects.extend((0..1000).map(|_| at(2.0, 1200.0)));
+        let exact = objects[1..]
+            .iter()
+            .map(|b| gravitational_force(&objects[0], b)[0] as f64)
+            .sum::<f64>();
+        let error = |compensated: bool| {
+            let force = Universe::pair_forces_on(&objects, 0, compensated);
+            (force[0] as f64 - exact).abs() / exact
+        };
+        assert!(error(false) > 1e-5, "{}", error(false));
+        assert!(error(true) < 1e-6, "{}", error(true));
+        let forces = Universe::pair_forces(&objects, true);
+        assert!((forces[0][0] as f64 - exact).abs() / exact < 1e-6);
+
+        let run = |incremental: bool| {
+            let mut universe = Universe::<2>::new();
+            universe.set_compensated_summation(true);
+            universe.set_incremental_broadphase(incremental);
+            for i in 0..20 {
+                let x = ((i * 7) % 20) as Float * 3.0;
+                universe.add_object(
+                    ObjectBuilder::new_at([x, (i % 3) as Float] * m)
+                        .with_mass(1e9 * kg)
+                        .with_velocity([(i % 5) as Float - 2.0, 0.0] * m / s)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            universe.step(0.05);
+            universe.state_hash()
+        };
+        assert_eq!(run(false), run(true));
+    }
+
+    #[test]
+    fn test_find() {
+        let mut universe = Universe::<2>::new();
+        for (i, mass) in [1.0, 50.0, 3.0, 80.0, 20.0].into_iter().enumerate() {
+            universe.add_object(
+                ObjectBuilder::new_at([3.0 * i as Float, 0.0] * m)
+                    .with_mass(mass * kg)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let heavy = universe.find(|o| o.mass() > 10.0 * kg);
+        assert_eq!(
+            heavy
+                .iter()
+                .map(|&id| universe.object(id).unwrap().mass())
+                .collect::<Vec<_>>(),
+            vec![50.0 * kg, 80.0 * kg, 20.0 * kg]
+        );
+
+        let first = universe.find_one(|o| o.mass() > 10.0 * kg);
+        assert_eq!(first, Some(heavy[0]));
+        assert_eq!(universe.find_one(|o| o.mass() > 100.0 * kg), None);
+        assert!(universe.find(|o| o.charge() != 0.0 * C).is_empty());
+    }
+
+    #[test]
+    fn test_initial_spin() {
+        let mut universe = Universe::<2>::new();
+        universe.add_object(
+            ObjectBuilder::new_at([0.0, 0.0] * m)
+                .with_angle(0.5)
+                .with_angular_velocity([0.0, 0.0, 2.0] * rad / s)
+                .build()
+                .unwrap(),
+        );
+        universe.step(1.0);
+        let object = &universe.objects()[0];
+        assert_eq!(object.angular_velocity(), [0.0, 0.0, 2.0] * rad / s);
+        let angle = object.orientation().angle();
+        assert!((angle - 2.5).abs() < 1e-3, "{angle}");
+        let heading = object.orientation().rotate(Vector::<3>::i);
+        let (sin, cos) = (2.5 as Float).sin_cos();
+        assert!((heading - [cos, sin, 0.0].into()).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn test_single_bounce() {
+        for height in [0.05, 0.4, 2.5] {
+            let mut universe = Universe::<2>::new();
+            universe.add_gravitational_field([0.0, -9.8] * N / kg);
+            universe.add_plane(Vector::<2>::j, 0.0 * m);
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, 1.0 + height] * m)
+                    .with_restitution(1.0)
+                    .build()
+                    .unwrap(),
+            );
+            let mut impact = 0.0;
+            let mut rebound = 0.0;
+            while rebound == 0.0 || universe.objects()[0].velocity()[1] > 0.0 {
+                let before = universe.objects()[0].velocity()[1];
+                universe.step_once();
+                let after = universe.objects()[0].velocity()[1];
+                if before < 0.0 && after > 0.0 {
+                    impact = -before;
+                }
+                if impact > 0.0 && rebound == 0.0 {
+                    rebound = after;
+                }
+            }
+            let expected = (2.0 * 9.8 * height).sqrt();
+            assert!(
+                (impact - expected).abs() < 1e-2 * expected,
+                "{impact} {expected}"
+            );
+            assert!(
+                (rebound - impact).abs() < 1e-2 * impact,
+                "{rebound} {impact}"
+            );
+        }
+
+        // Resting contacts last for every substep, bouncing each time would make the column
+        // jitter and sink into itself
+        let mut universe = Universe::<2>::new();
+        universe.add_gravitational_field([0.0, -9.8] * N / kg);
+        universe.add_plane(Vector::<2>::j, 0.0 * m);
+        for i in 0..4 {
+            universe.add_object(
+                ObjectBuilder::new_at([0.0, 1.0 + 2.0 * i as Float] * m)
+                    .with_restitution(1.0)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        universe.step(2.0);
+        for (i, object) in universe.objects().iter().enumerate() {
+            let height = object.position()[1] - (1.0 + 2.0 * i as Float);
+            assert!(height.abs() < 1e-4, "{i}: {height}");
+        }
     }
 }